@@ -0,0 +1,54 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use fta::analyze;
+use fta::config::get_default_config;
+use std::fs;
+use tempfile::TempDir;
+
+const SOURCE: &str = r#"
+export function process(items: number[], threshold: number): number[] {
+    const result: number[] = [];
+    for (const item of items) {
+        if (item > threshold && item % 2 === 0) {
+            result.push(item * 2);
+        } else if (item < 0 || item === threshold) {
+            result.push(-item);
+        } else {
+            result.push(item);
+        }
+    }
+    return result.sort((a, b) => a - b);
+}
+"#;
+
+fn create_project(file_count: usize) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    for directory in 0..10 {
+        let path = dir.path().join(format!("module_{}", directory));
+        fs::create_dir(&path).unwrap();
+        for file in 0..file_count / 10 {
+            fs::write(path.join(format!("file_{}.ts", file)), SOURCE.repeat(20)).unwrap();
+        }
+    }
+    dir
+}
+
+fn walk_benchmark(c: &mut Criterion) {
+    let dir = create_project(500);
+    let repo_path = dir.path().to_str().unwrap().to_string();
+
+    let mut group = c.benchmark_group("analyze");
+    group.sample_size(10);
+    for threads in [1, 0] {
+        let mut config = get_default_config();
+        config.threads = threads;
+        let name = match threads {
+            0 => "threads=auto".to_string(),
+            n => format!("threads={}", n),
+        };
+        group.bench_function(name, |b| b.iter(|| analyze(&repo_path, &config)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, walk_benchmark);
+criterion_main!(benches);
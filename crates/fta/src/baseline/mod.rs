@@ -0,0 +1,200 @@
+use crate::config::resolve_for_file;
+use crate::error::ScoreCapBreach;
+use crate::structs::{FileData, FtaConfigResolved};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+
+mod tests;
+
+#[derive(Debug, Clone)]
+pub struct BaselineError {
+    message: String,
+}
+
+impl fmt::Display for BaselineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BaselineError! {}", self.message)
+    }
+}
+
+/// A snapshot of every file's FTA score, used to stop existing files from getting worse.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct Baseline {
+    pub version: String,
+    pub files: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ScoreChange {
+    pub file_name: String,
+    pub baseline_score: f64,
+    pub fta_score: f64,
+}
+
+impl ScoreChange {
+    pub fn delta(&self) -> f64 {
+        self.fta_score - self.baseline_score
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct BaselineComparison {
+    /// Files whose score increased by more than the tolerance.
+    pub regressions: Vec<ScoreChange>,
+    /// Files whose score decreased.
+    pub improvements: Vec<ScoreChange>,
+    /// Files missing from the baseline whose score is beyond their score cap.
+    pub new_files_over_cap: Vec<ScoreCapBreach>,
+    /// Files in the baseline that are no longer part of the analysis.
+    pub removed_files: Vec<String>,
+}
+
+impl BaselineComparison {
+    pub fn has_failures(&self) -> bool {
+        !self.regressions.is_empty() || !self.new_files_over_cap.is_empty()
+    }
+
+    pub fn report(&self) -> String {
+        let mut report = format!(
+            "Baseline comparison: {} regression(s), {} improvement(s), {} new file(s) over the score cap, {} removed file(s).",
+            self.regressions.len(),
+            self.improvements.len(),
+            self.new_files_over_cap.len(),
+            self.removed_files.len()
+        );
+
+        if !self.regressions.is_empty() {
+            report.push_str("\nRegressions:");
+            for change in &self.regressions {
+                report.push_str(&format_change(change));
+            }
+        }
+        if !self.new_files_over_cap.is_empty() {
+            report.push_str("\nNew files beyond their score cap:");
+            for breach in &self.new_files_over_cap {
+                report.push_str(&format!(
+                    "\n  {}: {:.2} (score cap of {})",
+                    breach.file_name, breach.fta_score, breach.score_cap
+                ));
+            }
+        }
+        if !self.improvements.is_empty() {
+            report.push_str("\nImprovements:");
+            for change in &self.improvements {
+                report.push_str(&format_change(change));
+            }
+        }
+        if !self.removed_files.is_empty() {
+            report.push_str("\nRemoved files:");
+            for file_name in &self.removed_files {
+                report.push_str(&format!("\n  {}", file_name));
+            }
+        }
+
+        report
+    }
+}
+
+fn format_change(change: &ScoreChange) -> String {
+    format!(
+        "\n  {}: {:.2} -> {:.2} ({:+.2})",
+        change.file_name,
+        change.baseline_score,
+        change.fta_score,
+        change.delta()
+    )
+}
+
+pub fn create_baseline(file_data_list: &[FileData]) -> Baseline {
+    Baseline {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        files: file_data_list
+            .iter()
+            .map(|file_data| (file_data.file_name.clone(), file_data.fta_score))
+            .collect(),
+    }
+}
+
+pub fn write_baseline(path: &str, file_data_list: &[FileData]) -> Result<(), BaselineError> {
+    let baseline = create_baseline(file_data_list);
+    let content = serde_json::to_string_pretty(&baseline).unwrap();
+
+    fs::write(path, content).map_err(|err| BaselineError {
+        message: format!("Unable to write baseline file {}: {}", path, err),
+    })
+}
+
+pub fn read_baseline(path: &str) -> Result<Baseline, BaselineError> {
+    let content = fs::read_to_string(path).map_err(|err| BaselineError {
+        message: format!("Unable to read baseline file {}: {}", path, err),
+    })?;
+
+    serde_json::from_str(&content).map_err(|err| BaselineError {
+        message: format!("Invalid baseline file {}: {}", path, err),
+    })
+}
+
+/// Compares the analysis against a baseline. New files are checked against their own score cap,
+/// taking overrides into account. Regressions are sorted by the largest increase first,
+/// improvements by the largest decrease first.
+pub fn compare_to_baseline(
+    baseline: &Baseline,
+    file_data_list: &[FileData],
+    tolerance: f64,
+    config: &FtaConfigResolved,
+) -> BaselineComparison {
+    let mut comparison = BaselineComparison::default();
+
+    for file_data in file_data_list {
+        // Ignored files can't fail the comparison, but they aren't removed either
+        let file_config = resolve_for_file(config, &file_data.file_name);
+        let Some(score_cap) = file_data.score_cap(file_config.score_cap) else {
+            continue;
+        };
+
+        match baseline.files.get(&file_data.file_name) {
+            Some(&baseline_score) => {
+                let change = ScoreChange {
+                    file_name: file_data.file_name.clone(),
+                    baseline_score,
+                    fta_score: file_data.fta_score,
+                };
+                if change.delta() > tolerance {
+                    comparison.regressions.push(change);
+                } else if change.delta() < 0.0 {
+                    comparison.improvements.push(change);
+                }
+            }
+            None if file_data.fta_score > score_cap as f64 => {
+                comparison.new_files_over_cap.push(ScoreCapBreach {
+                    file_name: file_data.file_name.clone(),
+                    fta_score: file_data.fta_score,
+                    score_cap,
+                });
+            }
+            None => {}
+        }
+    }
+
+    let analyzed: HashSet<&str> = file_data_list
+        .iter()
+        .map(|file_data| file_data.file_name.as_str())
+        .collect();
+    comparison.removed_files = baseline
+        .files
+        .keys()
+        .filter(|file_name| !analyzed.contains(file_name.as_str()))
+        .cloned()
+        .collect();
+
+    comparison
+        .regressions
+        .sort_by(|a, b| b.delta().partial_cmp(&a.delta()).unwrap());
+    comparison
+        .improvements
+        .sort_by(|a, b| a.delta().partial_cmp(&b.delta()).unwrap());
+
+    comparison
+}
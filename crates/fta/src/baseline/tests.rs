@@ -0,0 +1,249 @@
+#[cfg(test)]
+mod tests {
+    use crate::baseline::{
+        compare_to_baseline, create_baseline, read_baseline, write_baseline, Baseline, ScoreChange,
+    };
+    use crate::config::get_default_config;
+    use crate::error::ScoreCapBreach;
+    use crate::structs::{test_file_data, ConfigOverride, FtaConfigResolved, Suppression};
+    use std::collections::BTreeMap;
+    use tempfile::NamedTempFile;
+
+    fn baseline(files: &[(&str, f64)]) -> Baseline {
+        Baseline {
+            version: "3.0.0".to_string(),
+            files: files
+                .iter()
+                .map(|(file_name, score)| (file_name.to_string(), *score))
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+
+    fn config(score_cap: usize) -> FtaConfigResolved {
+        FtaConfigResolved {
+            score_cap,
+            ..get_default_config()
+        }
+    }
+
+    #[test]
+    fn test_create_baseline() {
+        let baseline =
+            create_baseline(&[test_file_data("b.ts", 20.0), test_file_data("a.ts", 10.0)]);
+
+        assert_eq!(baseline.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            baseline.files.into_iter().collect::<Vec<_>>(),
+            vec![("a.ts".to_string(), 10.0), ("b.ts".to_string(), 20.0)]
+        );
+    }
+
+    #[test]
+    fn test_write_and_read_baseline() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path().to_str().unwrap();
+
+        write_baseline(path, &[test_file_data("a.ts", 10.5)]).unwrap();
+        let baseline = read_baseline(path).unwrap();
+
+        assert_eq!(baseline.files.get("a.ts"), Some(&10.5));
+    }
+
+    #[test]
+    fn test_read_invalid_baseline() {
+        assert!(read_baseline("nonexistent_baseline.json").is_err());
+
+        let temp_file = NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), "[1, 2, 3]").unwrap();
+        assert!(read_baseline(temp_file.path().to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_compare_unchanged() {
+        let comparison = compare_to_baseline(
+            &baseline(&[("a.ts", 10.0)]),
+            &[test_file_data("a.ts", 10.0)],
+            0.0,
+            &config(1000),
+        );
+
+        assert!(!comparison.has_failures());
+        assert_eq!(comparison, Default::default());
+    }
+
+    #[test]
+    fn test_compare_regressions_and_improvements() {
+        let comparison = compare_to_baseline(
+            &baseline(&[
+                ("a.ts", 10.0),
+                ("b.ts", 20.0),
+                ("c.ts", 30.0),
+                ("d.ts", 40.0),
+            ]),
+            &[
+                test_file_data("a.ts", 12.0),
+                test_file_data("b.ts", 25.0),
+                test_file_data("c.ts", 29.0),
+                test_file_data("d.ts", 40.5),
+            ],
+            1.0,
+            &config(1000),
+        );
+
+        assert!(comparison.has_failures());
+        assert_eq!(
+            comparison.regressions,
+            vec![
+                ScoreChange {
+                    file_name: "b.ts".to_string(),
+                    baseline_score: 20.0,
+                    fta_score: 25.0,
+                },
+                ScoreChange {
+                    file_name: "a.ts".to_string(),
+                    baseline_score: 10.0,
+                    fta_score: 12.0,
+                },
+            ]
+        );
+        assert_eq!(
+            comparison.improvements,
+            vec![ScoreChange {
+                file_name: "c.ts".to_string(),
+                baseline_score: 30.0,
+                fta_score: 29.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_compare_new_and_removed_files() {
+        let comparison = compare_to_baseline(
+            &baseline(&[("old.ts", 90.0)]),
+            &[
+                test_file_data("small.ts", 20.0),
+                test_file_data("big.ts", 80.0),
+            ],
+            0.0,
+            &config(50),
+        );
+
+        assert!(comparison.has_failures());
+        assert!(comparison.regressions.is_empty());
+        assert_eq!(
+            comparison.new_files_over_cap,
+            vec![ScoreCapBreach {
+                file_name: "big.ts".to_string(),
+                fta_score: 80.0,
+                score_cap: 50,
+            }]
+        );
+        assert_eq!(comparison.removed_files, vec!["old.ts".to_string()]);
+    }
+
+    #[test]
+    fn test_new_files_use_the_score_cap_of_their_overrides() {
+        let mut config = config(50);
+        config.overrides = vec![
+            ConfigOverride {
+                files: vec!["src/generated/**".to_string()],
+                score_cap: Some(90),
+                ..Default::default()
+            },
+            ConfigOverride {
+                files: vec!["src/strict/**".to_string()],
+                score_cap: Some(30),
+                ..Default::default()
+            },
+        ];
+
+        let comparison = compare_to_baseline(
+            &baseline(&[]),
+            &[
+                test_file_data("src/generated/api.ts", 80.0),
+                test_file_data("src/strict/core.ts", 40.0),
+            ],
+            0.0,
+            &config,
+        );
+
+        assert_eq!(
+            comparison.new_files_over_cap,
+            vec![ScoreCapBreach {
+                file_name: "src/strict/core.ts".to_string(),
+                fta_score: 40.0,
+                score_cap: 30,
+            }]
+        );
+        assert!(comparison.report().ends_with(
+            "New files beyond their score cap:\n  src/strict/core.ts: 40.00 (score cap of 30)"
+        ));
+    }
+
+    #[test]
+    fn test_existing_files_over_the_cap_are_allowed() {
+        let comparison = compare_to_baseline(
+            &baseline(&[("legacy.ts", 90.0)]),
+            &[test_file_data("legacy.ts", 85.0)],
+            0.0,
+            &config(50),
+        );
+
+        assert!(!comparison.has_failures());
+    }
+
+    #[test]
+    fn test_suppressed_files() {
+        let mut ignored = test_file_data("ignored.ts", 95.0);
+        ignored.suppression = Some(Suppression::IgnoreFile {
+            line: 1,
+            reason: None,
+        });
+        let mut capped = test_file_data("capped.ts", 75.0);
+        capped.suppression = Some(Suppression::ScoreCap {
+            line: 1,
+            score_cap: 80,
+            reason: None,
+        });
+
+        let comparison = compare_to_baseline(
+            &baseline(&[("ignored.ts", 60.0)]),
+            &[ignored, capped],
+            0.0,
+            &config(50),
+        );
+
+        assert!(!comparison.has_failures());
+        assert!(comparison.removed_files.is_empty());
+    }
+
+    #[test]
+    fn test_report() {
+        let comparison = compare_to_baseline(
+            &baseline(&[("a.ts", 10.0), ("b.ts", 20.0), ("old.ts", 5.0)]),
+            &[
+                test_file_data("a.ts", 15.0),
+                test_file_data("b.ts", 18.25),
+                test_file_data("new.ts", 70.0),
+            ],
+            0.0,
+            &config(50),
+        );
+
+        assert_eq!(
+            comparison.report(),
+            [
+                "Baseline comparison: 1 regression(s), 1 improvement(s), 1 new file(s) over the score cap, 1 removed file(s).",
+                "Regressions:",
+                "  a.ts: 10.00 -> 15.00 (+5.00)",
+                "New files beyond their score cap:",
+                "  new.ts: 70.00 (score cap of 50)",
+                "Improvements:",
+                "  b.ts: 20.00 -> 18.25 (-1.75)",
+                "Removed files:",
+                "  old.ts",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use crate::config::assessment_bands;
+use crate::structs::{FileData, FtaConfigResolved};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+mod tests;
+
+/// Bump this whenever the format of the cache file changes. Changes to the analysis itself are
+/// picked up from `ANALYSIS_SOURCES`.
+const CACHE_VERSION: u32 = 2;
+
+/// The source code of the modules that produce `FileData`. It's part of every cache key, so that
+/// results are never reused across changes to the scoring, even between releases.
+const ANALYSIS_SOURCES: [&str; 11] = [
+    include_str!("../lib.rs"),
+    include_str!("../structs/mod.rs"),
+    include_str!("../config/mod.rs"),
+    include_str!("../parse/mod.rs"),
+    include_str!("../sfc/mod.rs"),
+    include_str!("../suppression/mod.rs"),
+    include_str!("../utils/mod.rs"),
+    include_str!("../cyclo/mod.rs"),
+    include_str!("../cognitive/mod.rs"),
+    include_str!("../halstead/mod.rs"),
+    include_str!("../functions/mod.rs"),
+];
+
+const CACHE_FILE: &str = "cache.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    key: String,
+    file_data: FileData,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+/// Previous analysis results, keyed by the relative path of each file. An entry is only reused
+/// while its key matches, see `key`.
+#[derive(Debug)]
+pub struct Cache {
+    dir: PathBuf,
+    previous: BTreeMap<String, CacheEntry>,
+    current: Mutex<BTreeMap<String, CacheEntry>>,
+}
+
+/// A hash of `ANALYSIS_SOURCES`, which is computed once rather than for every file.
+fn analysis_fingerprint() -> &'static [u8] {
+    static FINGERPRINT: OnceLock<Vec<u8>> = OnceLock::new();
+    FINGERPRINT.get_or_init(|| {
+        let mut hasher = Sha256::new();
+        for source in ANALYSIS_SOURCES {
+            hasher.update(source);
+        }
+        hasher.finalize().to_vec()
+    })
+}
+
+/// The cache key of a file, covering its contents, the version of FTA, the code of the analysis
+/// and the parts of the config that affect it.
+pub fn key(source_code: &str, config: &FtaConfigResolved) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(CACHE_VERSION.to_le_bytes());
+    hasher.update(env!("CARGO_PKG_VERSION"));
+    hasher.update(analysis_fingerprint());
+    hasher.update([config.include_comments as u8]);
+    hasher.update(format!("{:?}", assessment_bands(config)));
+    hasher.update(source_code);
+
+    format!("{:x}", hasher.finalize())
+}
+
+impl Cache {
+    /// Loads the cache in `dir`. A missing, unreadable or outdated cache is treated as empty.
+    pub fn load(dir: &Path) -> Cache {
+        let previous = fs::read_to_string(dir.join(CACHE_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str::<CacheFile>(&content).ok())
+            .filter(|cache_file| cache_file.version == CACHE_VERSION)
+            .map(|cache_file| cache_file.entries)
+            .unwrap_or_default();
+        debug!(
+            "Loaded {} cached files from {}",
+            previous.len(),
+            dir.display()
+        );
+
+        Cache {
+            dir: dir.to_path_buf(),
+            previous,
+            current: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn get(&self, file_name: &str, key: &str) -> Option<FileData> {
+        let entry = self.previous.get(file_name)?;
+        if entry.key != key {
+            return None;
+        }
+
+        self.current
+            .lock()
+            .unwrap()
+            .insert(file_name.to_string(), entry.clone());
+        Some(entry.file_data.clone())
+    }
+
+    pub fn insert(&self, file_name: &str, key: String, file_data: &FileData) {
+        self.current.lock().unwrap().insert(
+            file_name.to_string(),
+            CacheEntry {
+                key,
+                file_data: file_data.clone(),
+            },
+        );
+    }
+
+    /// Writes the cache back to disk. With `prune`, only the files seen during this run are kept,
+    /// which should only be used after analyzing the whole project.
+    pub fn save(self, prune: bool) {
+        let mut entries = if prune {
+            BTreeMap::new()
+        } else {
+            self.previous
+        };
+        entries.extend(self.current.into_inner().unwrap());
+
+        let cache_file = CacheFile {
+            version: CACHE_VERSION,
+            entries,
+        };
+        let result = fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(self.dir.join(".gitignore"), "*\n"))
+            .and_then(|_| {
+                fs::write(
+                    self.dir.join(CACHE_FILE),
+                    serde_json::to_string(&cache_file).unwrap(),
+                )
+            });
+
+        // The cache only speeds up later runs, so failing to write it shouldn't fail this one
+        if let Err(err) = result {
+            warn!(
+                "Unable to write the cache to {}: {}",
+                self.dir.display(),
+                err
+            );
+        }
+    }
+}
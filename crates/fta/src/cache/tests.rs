@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod tests {
+    use crate::cache::{key, Cache, ANALYSIS_SOURCES};
+    use crate::config::get_default_config;
+    use crate::structs::test_file_data;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_key_changes_with_content_and_config() {
+        let config = get_default_config();
+        let source = "const a = 1;";
+
+        assert_eq!(key(source, &config), key(source, &config));
+        assert_ne!(key(source, &config), key("const a = 2;", &config));
+
+        let mut with_comments = get_default_config();
+        with_comments.include_comments = true;
+        assert_ne!(key(source, &config), key(source, &with_comments));
+
+        let mut with_thresholds = get_default_config();
+        with_thresholds.assessment_thresholds.could_be_better = 40.0;
+        assert_ne!(key(source, &config), key(source, &with_thresholds));
+
+        // Options that don't affect the analysis of a file leave the key alone
+        let mut with_threads = get_default_config();
+        with_threads.threads = 4;
+        assert_eq!(key(source, &config), key(source, &with_threads));
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let dir = TempDir::new().unwrap();
+
+        let cache = Cache::load(dir.path());
+        assert!(cache.get("a.ts", "key-a").is_none());
+        cache.insert("a.ts", "key-a".to_string(), &test_file_data("a.ts", 12.5));
+        cache.save(true);
+
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".gitignore")).unwrap(),
+            "*\n"
+        );
+
+        let cache = Cache::load(dir.path());
+        assert_eq!(cache.get("a.ts", "key-a").unwrap().fta_score, 12.5);
+        assert!(cache.get("a.ts", "outdated").is_none());
+        assert!(cache.get("b.ts", "key-a").is_none());
+    }
+
+    #[test]
+    fn test_cache_pruning() {
+        let dir = TempDir::new().unwrap();
+
+        let cache = Cache::load(dir.path());
+        cache.insert("a.ts", "key-a".to_string(), &test_file_data("a.ts", 10.0));
+        cache.insert("b.ts", "key-b".to_string(), &test_file_data("b.ts", 20.0));
+        cache.save(true);
+
+        // Without pruning, files that weren't seen are kept
+        let cache = Cache::load(dir.path());
+        assert!(cache.get("a.ts", "key-a").is_some());
+        cache.save(false);
+        assert!(Cache::load(dir.path()).get("b.ts", "key-b").is_some());
+
+        // With pruning, only files that were seen are kept
+        let cache = Cache::load(dir.path());
+        assert!(cache.get("a.ts", "key-a").is_some());
+        cache.save(true);
+        let cache = Cache::load(dir.path());
+        assert!(cache.get("a.ts", "key-a").is_some());
+        assert!(cache.get("b.ts", "key-b").is_none());
+    }
+
+    #[test]
+    fn test_invalid_cache_is_ignored() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("cache.json"), "{ not json").unwrap();
+
+        assert!(Cache::load(dir.path()).get("a.ts", "key-a").is_none());
+    }
+
+    #[test]
+    fn test_analysis_uses_the_cache() {
+        let project = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        let source = "function add(a: number, b: number) {\n  if (a > b) {\n    return a + b;\n  }\n  return b - a;\n}\nconsole.log(add(1, 2));\n";
+        fs::write(project.path().join("add.ts"), source).unwrap();
+        let repo_path = project.path().to_str().unwrap().to_string();
+
+        let mut config = get_default_config();
+        config.exclude_under = 0;
+        config.cache_dir = Some(cache_dir.path().to_str().unwrap().to_string());
+
+        let first = crate::analyze(&repo_path, &config).unwrap();
+        assert_eq!(first.files.len(), 1);
+
+        // Tamper with the cached result to tell it apart from a fresh analysis
+        let cache = Cache::load(cache_dir.path());
+        let mut cached = test_file_data("add.ts", 99.0);
+        cached.line_count = first.files[0].line_count;
+        cache.insert("add.ts", key(source, &config), &cached);
+        cache.save(true);
+
+        let second = crate::analyze(&repo_path, &config).unwrap();
+        assert_eq!(second.files[0].fta_score, 99.0);
+
+        // Changing the file invalidates the cached result
+        fs::write(
+            project.path().join("add.ts"),
+            source.replace("b - a", "a - b"),
+        )
+        .unwrap();
+        let third = crate::analyze(&repo_path, &config).unwrap();
+        assert_eq!(third.files[0].fta_score, first.files[0].fta_score);
+    }
+
+    #[test]
+    fn test_analysis_sources_cover_the_scoring() {
+        for function in [
+            "fn analyze_file(",
+            "fn calculate_fta_score(",
+            "fn cyclomatic_complexity(",
+            "fn cognitive_complexity(",
+            "fn analyze_functions(",
+        ] {
+            assert!(
+                ANALYSIS_SOURCES
+                    .iter()
+                    .any(|source| source.contains(function)),
+                "{} isn't part of the cache key",
+                function
+            );
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+mod tests;
+
+/// Cognitive complexity, as described by SonarSource's "Cognitive Complexity" white paper.
+///
+/// Unlike cyclomatic complexity, structures are penalised more heavily the deeper they are
+/// nested, while shorthand that doesn't hinder readability (such as a flat `switch`) is cheap.
+pub struct CognitiveVisitor {
+    complexity: usize,
+    nesting: usize,
+    function_depth: usize,
+    function_names: Vec<String>,
+}
+
+impl CognitiveVisitor {
+    fn new() -> Self {
+        CognitiveVisitor {
+            complexity: 0,
+            nesting: 0,
+            function_depth: 0,
+            function_names: Vec::new(),
+        }
+    }
+
+    /// Structural increment: +1 for the structure plus one for each level of nesting.
+    fn increment_nested(&mut self) {
+        self.complexity += 1 + self.nesting;
+    }
+
+    fn visit_nested<N: VisitWith<Self> + ?Sized>(&mut self, node: &N) {
+        self.nesting += 1;
+        node.visit_with(self);
+        self.nesting -= 1;
+    }
+
+    /// Nested functions add a level of nesting to everything inside them.
+    fn visit_function_like<N: VisitWith<Self> + ?Sized>(&mut self, name: Option<String>, node: &N) {
+        let nesting = self.nesting;
+        if self.function_depth > 0 {
+            self.nesting += 1;
+        }
+        self.function_depth += 1;
+        let named = name.is_some();
+        if let Some(name) = name {
+            self.function_names.push(name);
+        }
+
+        node.visit_children_with(self);
+
+        if named {
+            self.function_names.pop();
+        }
+        self.function_depth -= 1;
+        self.nesting = nesting;
+    }
+
+    /// `else if` and `else` are hybrid increments: they add one, but not for nesting.
+    fn visit_alt(&mut self, alt: &Stmt) {
+        self.complexity += 1;
+        match alt {
+            Stmt::If(else_if) => {
+                else_if.test.visit_with(self);
+                self.visit_nested(&*else_if.cons);
+                if let Some(alt) = &else_if.alt {
+                    self.visit_alt(alt);
+                }
+            }
+            _ => self.visit_nested(alt),
+        }
+    }
+}
+
+fn is_logical(op: BinaryOp) -> bool {
+    matches!(
+        op,
+        BinaryOp::LogicalAnd | BinaryOp::LogicalOr | BinaryOp::NullishCoalescing
+    )
+}
+
+/// Flattens a chain of logical operators into its operators (in source order) and operands.
+fn flatten_logical<'a>(expr: &'a Expr, ops: &mut Vec<BinaryOp>, operands: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::Bin(bin) if is_logical(bin.op) => {
+            flatten_logical(&bin.left, ops, operands);
+            ops.push(bin.op);
+            flatten_logical(&bin.right, ops, operands);
+        }
+        Expr::Paren(paren) => flatten_logical(&paren.expr, ops, operands),
+        _ => operands.push(expr),
+    }
+}
+
+impl Visit for CognitiveVisitor {
+    fn visit_if_stmt(&mut self, node: &IfStmt) {
+        self.increment_nested();
+        node.test.visit_with(self);
+        self.visit_nested(&*node.cons);
+        if let Some(alt) = &node.alt {
+            self.visit_alt(alt);
+        }
+    }
+
+    fn visit_cond_expr(&mut self, node: &CondExpr) {
+        self.increment_nested();
+        node.test.visit_with(self);
+        self.visit_nested(&*node.cons);
+        self.visit_nested(&*node.alt);
+    }
+
+    fn visit_switch_stmt(&mut self, node: &SwitchStmt) {
+        self.increment_nested();
+        node.discriminant.visit_with(self);
+        self.visit_nested(&node.cases);
+    }
+
+    fn visit_for_stmt(&mut self, node: &ForStmt) {
+        self.increment_nested();
+        node.init.visit_with(self);
+        node.test.visit_with(self);
+        node.update.visit_with(self);
+        self.visit_nested(&*node.body);
+    }
+
+    fn visit_for_in_stmt(&mut self, node: &ForInStmt) {
+        self.increment_nested();
+        node.left.visit_with(self);
+        node.right.visit_with(self);
+        self.visit_nested(&*node.body);
+    }
+
+    fn visit_for_of_stmt(&mut self, node: &ForOfStmt) {
+        self.increment_nested();
+        node.left.visit_with(self);
+        node.right.visit_with(self);
+        self.visit_nested(&*node.body);
+    }
+
+    fn visit_while_stmt(&mut self, node: &WhileStmt) {
+        self.increment_nested();
+        node.test.visit_with(self);
+        self.visit_nested(&*node.body);
+    }
+
+    fn visit_do_while_stmt(&mut self, node: &DoWhileStmt) {
+        self.increment_nested();
+        self.visit_nested(&*node.body);
+        node.test.visit_with(self);
+    }
+
+    fn visit_catch_clause(&mut self, node: &CatchClause) {
+        self.increment_nested();
+        node.param.visit_with(self);
+        self.visit_nested(&node.body);
+    }
+
+    fn visit_break_stmt(&mut self, node: &BreakStmt) {
+        if node.label.is_some() {
+            self.complexity += 1;
+        }
+    }
+
+    fn visit_continue_stmt(&mut self, node: &ContinueStmt) {
+        if node.label.is_some() {
+            self.complexity += 1;
+        }
+    }
+
+    fn visit_bin_expr(&mut self, node: &BinExpr) {
+        if !is_logical(node.op) {
+            node.visit_children_with(self);
+            return;
+        }
+
+        // Each run of identical operators counts once, so `a && b && c` is 1 but `a && b || c` is 2
+        let mut ops = Vec::new();
+        let mut operands = Vec::new();
+        flatten_logical(&node.left, &mut ops, &mut operands);
+        ops.push(node.op);
+        flatten_logical(&node.right, &mut ops, &mut operands);
+
+        self.complexity += 1 + ops.windows(2).filter(|pair| pair[0] != pair[1]).count();
+
+        for operand in operands {
+            operand.visit_with(self);
+        }
+    }
+
+    fn visit_call_expr(&mut self, node: &CallExpr) {
+        if let Callee::Expr(callee) = &node.callee {
+            if let Expr::Ident(ident) = &**callee {
+                if self.function_names.iter().any(|name| *name == *ident.sym) {
+                    self.complexity += 1;
+                }
+            }
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_fn_decl(&mut self, node: &FnDecl) {
+        self.visit_function_like(Some(node.ident.sym.to_string()), &*node.function);
+    }
+
+    fn visit_fn_expr(&mut self, node: &FnExpr) {
+        let name = node.ident.as_ref().map(|ident| ident.sym.to_string());
+        self.visit_function_like(name, &*node.function);
+    }
+
+    fn visit_arrow_expr(&mut self, node: &ArrowExpr) {
+        self.visit_function_like(None, node);
+    }
+
+    fn visit_var_declarator(&mut self, node: &VarDeclarator) {
+        // `const walk = () => { walk() }` is recursive too
+        if let (Pat::Ident(binding), Some(init)) = (&node.name, &node.init) {
+            if let Expr::Arrow(arrow) = &**init {
+                self.visit_function_like(Some(binding.id.sym.to_string()), arrow);
+                return;
+            }
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_class_method(&mut self, node: &ClassMethod) {
+        node.key.visit_with(self);
+        self.visit_function_like(None, &*node.function);
+    }
+
+    fn visit_private_method(&mut self, node: &PrivateMethod) {
+        self.visit_function_like(None, &*node.function);
+    }
+
+    fn visit_constructor(&mut self, node: &Constructor) {
+        self.visit_function_like(None, node);
+    }
+
+    fn visit_method_prop(&mut self, node: &MethodProp) {
+        node.key.visit_with(self);
+        self.visit_function_like(None, &*node.function);
+    }
+
+    fn visit_getter_prop(&mut self, node: &GetterProp) {
+        self.visit_function_like(None, node);
+    }
+
+    fn visit_setter_prop(&mut self, node: &SetterProp) {
+        self.visit_function_like(None, node);
+    }
+}
+
+pub fn cognitive_complexity(module: &Module) -> usize {
+    let mut visitor = CognitiveVisitor::new();
+    visitor.visit_module(module);
+    visitor.complexity
+}
+
+/// Cognitive complexity of a single function-like node. When the function has a name, calls to
+/// it from within its body count as recursion.
+pub fn node_cognitive_complexity<N: VisitWith<CognitiveVisitor>>(
+    node: &N,
+    name: Option<&str>,
+) -> usize {
+    let mut visitor = CognitiveVisitor::new();
+    visitor.function_depth = 1;
+    visitor.function_names.extend(name.map(str::to_string));
+    node.visit_children_with(&mut visitor);
+    visitor.complexity
+}
@@ -0,0 +1,163 @@
+#[cfg(test)]
+mod tests {
+    use crate::cognitive::cognitive_complexity;
+    use crate::parse::parse_module;
+    use swc_ecma_ast::Module;
+
+    fn parse(src: &str) -> Module {
+        match parse_module(src, false, false) {
+            (Ok(module), _line_count) => module,
+            (Err(_err), _) => {
+                panic!("failed");
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_module() {
+        let ts_code = r#"
+            /* Empty TypeScript code */
+        "#;
+        let module = parse(ts_code);
+        assert_eq!(cognitive_complexity(&module), 0);
+    }
+
+    #[test]
+    fn test_single_if() {
+        let ts_code = r#"
+            if (x > 0) {
+                console.log("x is positive");
+            }
+        "#;
+        let module = parse(ts_code);
+        assert_eq!(cognitive_complexity(&module), 1);
+    }
+
+    #[test]
+    fn test_if_else_if_else() {
+        let ts_code = r#"
+            if (x > 0) {
+                console.log("positive");
+            } else if (x < 0) {
+                console.log("negative");
+            } else {
+                console.log("zero");
+            }
+        "#;
+        let module = parse(ts_code);
+        assert_eq!(cognitive_complexity(&module), 3);
+    }
+
+    #[test]
+    fn test_nesting_increments() {
+        let ts_code = r#"
+            for (const item of items) {
+                if (item) {
+                    while (item.next) {
+                        item = item.next;
+                    }
+                }
+            }
+        "#;
+        let module = parse(ts_code);
+        // for (+1), if (+1, +1 nesting), while (+1, +2 nesting)
+        assert_eq!(cognitive_complexity(&module), 6);
+    }
+
+    #[test]
+    fn test_flat_switch_is_cheap() {
+        let ts_code = r#"
+            function getWords(number) {
+                switch (number) {
+                    case 1:
+                        return "one";
+                    case 2:
+                        return "a couple";
+                    case 3:
+                        return "a few";
+                    default:
+                        return "lots";
+                }
+            }
+        "#;
+        let module = parse(ts_code);
+        assert_eq!(cognitive_complexity(&module), 1);
+    }
+
+    #[test]
+    fn test_labelled_continue() {
+        let ts_code = r#"
+            function sumOfPrimes(max) {
+                let total = 0;
+                OUT: for (let i = 1; i <= max; ++i) {
+                    for (let j = 2; j < i; ++j) {
+                        if (i % j == 0) {
+                            continue OUT;
+                        }
+                    }
+                    total += i;
+                }
+                return total;
+            }
+        "#;
+        let module = parse(ts_code);
+        assert_eq!(cognitive_complexity(&module), 7);
+    }
+
+    #[test]
+    fn test_boolean_operator_sequences() {
+        let ts_code = r#"
+            const a = x && y && z;
+            const b = x && y || z;
+            const c = x && (y || z) && w;
+        "#;
+        let module = parse(ts_code);
+        // 1 + 2 + 3
+        assert_eq!(cognitive_complexity(&module), 6);
+    }
+
+    #[test]
+    fn test_recursion() {
+        let ts_code = r#"
+            function factorial(n) {
+                if (n <= 1) {
+                    return 1;
+                }
+                return n * factorial(n - 1);
+            }
+            const walk = (node) => node.children.forEach((child) => walk(child));
+        "#;
+        let module = parse(ts_code);
+        assert_eq!(cognitive_complexity(&module), 3);
+    }
+
+    #[test]
+    fn test_nested_functions_increase_nesting() {
+        let ts_code = r#"
+            function outer(items) {
+                return items.map((item) => {
+                    if (item) {
+                        return 1;
+                    }
+                    return 0;
+                });
+            }
+        "#;
+        let module = parse(ts_code);
+        assert_eq!(cognitive_complexity(&module), 2);
+    }
+
+    #[test]
+    fn test_ternary_and_catch() {
+        let ts_code = r#"
+            try {
+                run();
+            } catch (err) {
+                report(err ? err.message : "unknown");
+            }
+        "#;
+        let module = parse(ts_code);
+        // catch (+1), ternary (+1, +1 nesting)
+        assert_eq!(cognitive_complexity(&module), 3);
+    }
+}
@@ -1,27 +1,23 @@
-use crate::structs::{FtaConfigOptional, FtaConfigResolved};
+use crate::error::FtaError;
+use crate::structs::{
+    AssessmentBand, AssessmentThresholds, ConfigOverride, FtaConfigOptional, FtaConfigResolved,
+    Severity,
+};
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::{de, Deserialize, Deserializer};
+use std::borrow::Cow;
 use std::fmt;
-use std::fs::File;
-use std::io::Read;
+use std::fs;
 use std::path::Path;
+use std::sync::{Arc, OnceLock};
 
 mod integration_tests;
 mod tests;
 
-#[derive(Debug, Clone)]
-pub struct ConfigError {
-    message: String,
-}
-
-impl fmt::Display for ConfigError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "ConfigError! {}", self.message)
-    }
-}
-
 impl From<FtaConfigOptional> for FtaConfigResolved {
     fn from(opt_config: FtaConfigOptional) -> Self {
         let default_config = get_default_config();
-        FtaConfigResolved {
+        compile_overrides(FtaConfigResolved {
             extensions: opt_config.extensions.unwrap_or(default_config.extensions),
             exclude_filenames: opt_config
                 .exclude_filenames
@@ -39,7 +35,19 @@ impl From<FtaConfigOptional> for FtaConfigResolved {
             exclude_under: opt_config
                 .exclude_under
                 .unwrap_or(default_config.exclude_under),
-        }
+            baseline_tolerance: opt_config
+                .baseline_tolerance
+                .unwrap_or(default_config.baseline_tolerance),
+            threads: opt_config.threads.unwrap_or(default_config.threads),
+            assessment_thresholds: opt_config
+                .assessment_thresholds
+                .unwrap_or(default_config.assessment_thresholds),
+            assessment_bands: opt_config
+                .assessment_bands
+                .or(default_config.assessment_bands),
+            overrides: opt_config.overrides.unwrap_or(default_config.overrides),
+            cache_dir: default_config.cache_dir,
+        })
     }
 }
 
@@ -65,25 +73,80 @@ pub fn get_default_config() -> FtaConfigResolved {
         score_cap: 1000,
         include_comments: false,
         exclude_under: 6,
+        baseline_tolerance: 0.0,
+        threads: 0,
+        assessment_thresholds: Default::default(),
+        assessment_bands: None,
+        overrides: vec![],
+        cache_dir: None,
     };
 
     default_config
 }
 
+/// Strictly parses the contents of a config file, so that typos and values of the wrong type are
+/// reported rather than silently falling back to the defaults.
+fn parse_config(config_path: &str, content: &str) -> Result<FtaConfigOptional, FtaError> {
+    serde_json::from_str(content).map_err(|err| {
+        // serde_json appends the location to its messages, which is reported separately
+        let location = format!(" at line {} column {}", err.line(), err.column());
+        let message = err.to_string();
+        // Errors about the config as a whole have no location, so they point at its end
+        let (line, column) = match err.line() {
+            0 => end_position(content),
+            line => (line, err.column()),
+        };
+        FtaError::ConfigParse {
+            path: config_path.to_string(),
+            message: message.trim_end_matches(&location).to_string(),
+            line,
+            column,
+        }
+    })
+}
+
+/// The one-based line and column of the last character of `content`, ignoring trailing whitespace.
+fn end_position(content: &str) -> (usize, usize) {
+    let content = content.trim_end();
+    let line = content.lines().count().max(1);
+    let column = content
+        .lines()
+        .last()
+        .map_or(0, |last| last.chars().count());
+
+    (line, column)
+}
+
+/// Rejects a config or override that sets both ways of assessing files, as the bands would
+/// silently replace the thresholds.
+pub fn check_assessment_options<E: de::Error>(
+    assessment_thresholds: &Option<AssessmentThresholds>,
+    assessment_bands: &Option<Vec<AssessmentBand>>,
+) -> Result<(), E> {
+    if assessment_thresholds.is_some() && assessment_bands.is_some() {
+        return Err(de::Error::custom(
+            "assessment_thresholds and assessment_bands can't both be set, as the bands replace the thresholds",
+        ));
+    }
+
+    Ok(())
+}
+
 pub fn read_config(
     config_path: String,
     path_specified_by_user: bool,
-) -> Result<FtaConfigResolved, ConfigError> {
+) -> Result<FtaConfigResolved, FtaError> {
     let default_config = get_default_config();
     if Path::new(&config_path).exists() {
-        let mut file = File::open(config_path).unwrap();
-        let mut content = String::new();
-        file.read_to_string(&mut content).unwrap();
-        let provided_config: FtaConfigOptional = serde_json::from_str(&content).unwrap_or_default();
+        let content = fs::read_to_string(&config_path).map_err(|err| FtaError::ConfigIo {
+            path: config_path.clone(),
+            message: err.to_string(),
+        })?;
+        let provided_config = parse_config(&config_path, &content)?;
 
         // For extensions, filenames and exclude_directories,
         // user-provided values are added to the defaults.
-        return Result::Ok(FtaConfigResolved {
+        return Result::Ok(compile_overrides(FtaConfigResolved {
             extensions: {
                 let mut extensions = default_config.extensions;
                 if let Some(mut provided) = provided_config.extensions {
@@ -117,14 +180,177 @@ pub fn read_config(
             include_comments: provided_config
                 .include_comments
                 .unwrap_or(default_config.include_comments),
-        });
+            baseline_tolerance: provided_config
+                .baseline_tolerance
+                .unwrap_or(default_config.baseline_tolerance),
+            threads: provided_config.threads.unwrap_or(default_config.threads),
+            assessment_thresholds: provided_config
+                .assessment_thresholds
+                .unwrap_or(default_config.assessment_thresholds),
+            assessment_bands: provided_config
+                .assessment_bands
+                .or(default_config.assessment_bands),
+            overrides: provided_config
+                .overrides
+                .unwrap_or(default_config.overrides),
+            cache_dir: default_config.cache_dir,
+        }));
     }
 
     if !path_specified_by_user {
         return Result::Ok(default_config);
     }
 
-    Result::Err(ConfigError {
-        message: format!("Config file not found at file path: {}", config_path),
+    Result::Err(FtaError::ConfigIo {
+        path: config_path,
+        message: "Config file not found".to_string(),
     })
 }
+
+/// Override globs are matched against paths relative to the project, and `*` doesn't match `/`.
+/// Use `**` to match any number of directories, e.g. `src/**/*.test.ts`.
+fn override_glob(pattern: &str) -> Result<Glob, globset::Error> {
+    GlobBuilder::new(pattern).literal_separator(true).build()
+}
+
+/// Rejects invalid override globs while parsing, so they're reported with their location.
+pub fn deserialize_globs<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let patterns = Vec::<String>::deserialize(deserializer)?;
+    for pattern in &patterns {
+        override_glob(pattern).map_err(de::Error::custom)?;
+    }
+
+    Ok(patterns)
+}
+
+/// The compiled `files` globs of an override. Overrides are matched against every file, so their
+/// globs are compiled once: when the config is read, or on first use for configs built in code.
+#[derive(Clone, Default)]
+pub struct OverrideMatcher(OnceLock<Arc<GlobSet>>);
+
+impl OverrideMatcher {
+    fn glob_set(&self, patterns: &[String]) -> &GlobSet {
+        self.0.get_or_init(|| {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in patterns {
+                // Patterns are validated when the config is read
+                if let Ok(glob) = override_glob(pattern) {
+                    builder.add(glob);
+                }
+            }
+            Arc::new(builder.build().unwrap_or_else(|_| GlobSet::empty()))
+        })
+    }
+}
+
+// The matcher is derived from the `files` of its override, which are compared instead
+impl PartialEq for OverrideMatcher {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for OverrideMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("OverrideMatcher")
+    }
+}
+
+fn override_matches(config_override: &ConfigOverride, file_name: &str) -> bool {
+    config_override
+        .matcher
+        .glob_set(&config_override.files)
+        .is_match(file_name.replace('\\', "/"))
+}
+
+fn compile_overrides(config: FtaConfigResolved) -> FtaConfigResolved {
+    for config_override in &config.overrides {
+        config_override.matcher.glob_set(&config_override.files);
+    }
+
+    config
+}
+
+/// Resolves the config for a single file by applying every override that matches it, in order,
+/// so that later overrides win. `file_name` is relative to the project.
+pub fn resolve_for_file<'a>(
+    config: &'a FtaConfigResolved,
+    file_name: &str,
+) -> Cow<'a, FtaConfigResolved> {
+    let mut resolved = Cow::Borrowed(config);
+
+    for config_override in &config.overrides {
+        if !override_matches(config_override, file_name) {
+            continue;
+        }
+
+        let file_config = resolved.to_mut();
+        if let Some(score_cap) = config_override.score_cap {
+            file_config.score_cap = score_cap;
+        }
+        if let Some(include_comments) = config_override.include_comments {
+            file_config.include_comments = include_comments;
+        }
+        if let Some(exclude_under) = config_override.exclude_under {
+            file_config.exclude_under = exclude_under;
+        }
+        if let Some(assessment_thresholds) = config_override.assessment_thresholds {
+            // The thresholds replace any bands the file would otherwise be assessed by
+            file_config.assessment_thresholds = assessment_thresholds;
+            file_config.assessment_bands = None;
+        }
+        if let Some(assessment_bands) = &config_override.assessment_bands {
+            file_config.assessment_bands = Some(assessment_bands.clone());
+        }
+    }
+
+    resolved
+}
+
+/// Rejects an empty list of bands, as every file needs to be assessed.
+pub fn deserialize_bands<'de, D>(deserializer: D) -> Result<Option<Vec<AssessmentBand>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let bands = Option::<Vec<AssessmentBand>>::deserialize(deserializer)?;
+    if bands.as_ref().is_some_and(|bands| bands.is_empty()) {
+        return Err(de::Error::custom(
+            "assessment_bands must contain at least one band",
+        ));
+    }
+
+    Ok(bands)
+}
+
+/// The bands files are assessed by: either the custom `assessment_bands`, or "OK", "Could be
+/// better" and "Needs improvement" split at the `assessment_thresholds`.
+pub fn assessment_bands(config: &FtaConfigResolved) -> Vec<AssessmentBand> {
+    if let Some(bands) = &config.assessment_bands {
+        return bands.clone();
+    }
+
+    let AssessmentThresholds {
+        could_be_better,
+        needs_improvement,
+    } = config.assessment_thresholds;
+    vec![
+        AssessmentBand {
+            label: "OK".to_string(),
+            above: None,
+            severity: Severity::None,
+        },
+        AssessmentBand {
+            label: "Could be better".to_string(),
+            above: Some(could_be_better),
+            severity: Severity::Warning,
+        },
+        AssessmentBand {
+            label: "Needs improvement".to_string(),
+            above: Some(needs_improvement),
+            severity: Severity::Error,
+        },
+    ]
+}
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod tests {
-    use crate::config::read_config;
+    use crate::config::{assessment_bands, get_default_config, read_config, resolve_for_file};
+    use crate::error::FtaError;
+    use crate::structs::{AssessmentBand, AssessmentThresholds, ConfigOverride, Severity};
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -20,7 +22,9 @@ mod tests {
         "exclude_under": 10,
         "output_limit": 2500,
         "score_cap": 500,
-        "include_comments": true
+        "include_comments": true,
+        "baseline_tolerance": 0.5,
+        "threads": 4
     }
     "#;
 
@@ -59,6 +63,8 @@ mod tests {
         assert_eq!(config.output_limit, 2500);
         assert_eq!(config.score_cap, 500);
         assert_eq!(config.include_comments, true);
+        assert_eq!(config.baseline_tolerance, 0.5);
+        assert_eq!(config.threads, 4);
     }
 
     #[test]
@@ -140,6 +146,7 @@ mod tests {
         assert_eq!(config.output_limit, 5000);
         assert_eq!(config.score_cap, 1000);
         assert_eq!(config.include_comments, false);
+        assert_eq!(config.threads, 0);
     }
 
     #[test]
@@ -198,4 +205,345 @@ mod tests {
 
         assert!(config.is_err(), "Expected error, got {:?}", config);
     }
+
+    #[test]
+    fn test_read_config_with_invalid_json() {
+        let temp_file = create_temp_file("{\n    \"score_cap\": 50,\n}");
+        let path = temp_file.path().to_str().unwrap();
+
+        let config = read_config(path.to_string(), false);
+
+        assert!(
+            matches!(
+                config,
+                Err(FtaError::ConfigParse {
+                    line: 3,
+                    column: 1,
+                    ..
+                })
+            ),
+            "Expected a parse error, got {:?}",
+            config
+        );
+    }
+
+    #[test]
+    fn test_read_config_with_wrong_type() {
+        let temp_file =
+            create_temp_file("{\n    \"output_limit\": 10,\n    \"score_cap\": \"50\"\n}");
+        let path = temp_file.path().to_str().unwrap();
+
+        match read_config(path.to_string(), false) {
+            Err(FtaError::ConfigParse {
+                message,
+                line,
+                column,
+                ..
+            }) => {
+                assert_eq!(
+                    message,
+                    "invalid type: string \"50\", expected usize".to_string()
+                );
+                assert_eq!((line, column), (3, 21));
+            }
+            config => panic!("Expected a parse error, got {:?}", config),
+        }
+    }
+
+    #[test]
+    fn test_read_config_with_overrides() {
+        let valid_json = r#"
+    {
+        "score_cap": 50,
+        "assessment_thresholds": { "could_be_better": 40 },
+        "overrides": [
+            {
+                "files": ["src/generated/**", "**/*.test.ts"],
+                "score_cap": 90,
+                "exclude_under": 20
+            },
+            {
+                "files": ["src/generated/legacy/**"],
+                "include_comments": true,
+                "assessment_thresholds": { "could_be_better": 70, "needs_improvement": 80 }
+            }
+        ]
+    }
+    "#;
+
+        let temp_file = create_temp_file(valid_json);
+        let path = temp_file.path().to_str().unwrap();
+
+        let config = read_config(path.to_string(), false).unwrap();
+
+        assert_eq!(
+            config.assessment_thresholds,
+            AssessmentThresholds {
+                could_be_better: 40.0,
+                needs_improvement: 60.0,
+            }
+        );
+        assert_eq!(
+            config.overrides,
+            vec![
+                ConfigOverride {
+                    files: vec!["src/generated/**".to_string(), "**/*.test.ts".to_string()],
+                    score_cap: Some(90),
+                    exclude_under: Some(20),
+                    ..Default::default()
+                },
+                ConfigOverride {
+                    files: vec!["src/generated/legacy/**".to_string()],
+                    include_comments: Some(true),
+                    assessment_thresholds: Some(AssessmentThresholds {
+                        could_be_better: 70.0,
+                        needs_improvement: 80.0,
+                    }),
+                    ..Default::default()
+                },
+            ]
+        );
+        // The globs are compiled once, when the config is read
+        assert!(config
+            .overrides
+            .iter()
+            .all(|config_override| config_override.matcher.0.get().is_some()));
+    }
+
+    #[test]
+    fn test_read_config_with_invalid_override_glob() {
+        let temp_file = create_temp_file(r#"{ "overrides": [{ "files": ["src/[a-"] }] }"#);
+        let path = temp_file.path().to_str().unwrap();
+
+        let config = read_config(path.to_string(), false);
+
+        assert!(
+            matches!(config, Err(FtaError::ConfigParse { line: 1, .. })),
+            "Expected a parse error, got {:?}",
+            config
+        );
+    }
+
+    #[test]
+    fn test_resolve_for_file() {
+        let mut config = get_default_config();
+        config.score_cap = 50;
+        config.overrides = vec![
+            ConfigOverride {
+                files: vec!["src/generated/**".to_string(), "*.config.ts".to_string()],
+                score_cap: Some(90),
+                exclude_under: Some(20),
+                ..Default::default()
+            },
+            ConfigOverride {
+                files: vec!["src/generated/legacy/**".to_string()],
+                score_cap: Some(120),
+                ..Default::default()
+            },
+        ];
+
+        let resolved = resolve_for_file(&config, "src/app.ts");
+        assert_eq!((resolved.score_cap, resolved.exclude_under), (50, 6));
+        // Configs built in code compile their globs on first use, and keep them
+        assert!(config.overrides[0].matcher.0.get().is_some());
+
+        let resolved = resolve_for_file(&config, "src/generated/client.ts");
+        assert_eq!((resolved.score_cap, resolved.exclude_under), (90, 20));
+
+        // Later overrides win, but only for the options they set
+        let resolved = resolve_for_file(&config, "src/generated/legacy/client.ts");
+        assert_eq!((resolved.score_cap, resolved.exclude_under), (120, 20));
+
+        // `*` doesn't match across directories
+        assert_eq!(resolve_for_file(&config, "vite.config.ts").score_cap, 90);
+        assert_eq!(
+            resolve_for_file(&config, "src/vite.config.ts").score_cap,
+            50
+        );
+
+        // Windows paths are matched with forward slashes
+        assert_eq!(
+            resolve_for_file(&config, "src\\generated\\client.ts").score_cap,
+            90
+        );
+    }
+
+    #[test]
+    fn test_read_config_with_assessment_bands() {
+        let valid_json = r#"
+    {
+        "assessment_bands": [
+            { "label": "Great" },
+            { "label": "Risky", "above": 40, "severity": "note" },
+            { "label": "Critical", "above": 70, "severity": "error" }
+        ],
+        "overrides": [
+            {
+                "files": ["src/legacy/**"],
+                "assessment_bands": [{ "label": "Legacy" }]
+            }
+        ]
+    }
+    "#;
+
+        let temp_file = create_temp_file(valid_json);
+        let path = temp_file.path().to_str().unwrap();
+
+        let config = read_config(path.to_string(), false).unwrap();
+
+        assert_eq!(
+            assessment_bands(&config),
+            vec![
+                AssessmentBand {
+                    label: "Great".to_string(),
+                    above: None,
+                    severity: Severity::None,
+                },
+                AssessmentBand {
+                    label: "Risky".to_string(),
+                    above: Some(40.0),
+                    severity: Severity::Note,
+                },
+                AssessmentBand {
+                    label: "Critical".to_string(),
+                    above: Some(70.0),
+                    severity: Severity::Error,
+                },
+            ]
+        );
+        assert_eq!(
+            assessment_bands(&resolve_for_file(&config, "src/legacy/app.ts")),
+            vec![AssessmentBand {
+                label: "Legacy".to_string(),
+                above: None,
+                severity: Severity::None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_read_config_with_invalid_assessment_bands() {
+        for invalid_json in [
+            r#"{ "assessment_bands": [] }"#,
+            r#"{ "assessment_bands": [{ "label": "Bad", "severity": "fatal" }] }"#,
+            r#"{ "overrides": [{ "files": ["**"], "assessment_bands": [] }] }"#,
+        ] {
+            let temp_file = create_temp_file(invalid_json);
+            let path = temp_file.path().to_str().unwrap();
+
+            let config = read_config(path.to_string(), false);
+
+            assert!(
+                matches!(config, Err(FtaError::ConfigParse { .. })),
+                "Expected a parse error for {}, got {:?}",
+                invalid_json,
+                config
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_config_with_thresholds_and_bands() {
+        let top_level = "{\n    \"assessment_thresholds\": { \"could_be_better\": 40 },\n    \"assessment_bands\": [{ \"label\": \"OK\" }]\n}\n";
+        let in_override = "{\n    \"overrides\": [\n        {\n            \"files\": [\"**\"],\n            \"assessment_thresholds\": { \"could_be_better\": 40 },\n            \"assessment_bands\": [{ \"label\": \"OK\" }]\n        }\n    ]\n}";
+
+        for (invalid_json, expected_position) in [(top_level, (4, 1)), (in_override, (8, 5))] {
+            let temp_file = create_temp_file(invalid_json);
+            let path = temp_file.path().to_str().unwrap();
+
+            match read_config(path.to_string(), false) {
+                Err(FtaError::ConfigParse {
+                    message,
+                    line,
+                    column,
+                    ..
+                }) => {
+                    assert_eq!(
+                        message,
+                        "assessment_thresholds and assessment_bands can't both be set, as the bands replace the thresholds"
+                    );
+                    assert_eq!((line, column), expected_position);
+                }
+                config => panic!("Expected a parse error, got {:?}", config),
+            }
+        }
+    }
+
+    #[test]
+    fn test_override_thresholds_replace_bands() {
+        let mut config = get_default_config();
+        config.assessment_bands = Some(vec![AssessmentBand {
+            label: "Everything".to_string(),
+            above: None,
+            severity: Severity::None,
+        }]);
+        config.overrides = vec![ConfigOverride {
+            files: vec!["src/legacy/**".to_string()],
+            assessment_thresholds: Some(AssessmentThresholds {
+                could_be_better: 70.0,
+                needs_improvement: 80.0,
+            }),
+            ..Default::default()
+        }];
+
+        let resolved = resolve_for_file(&config, "src/legacy/app.ts");
+        assert_eq!(
+            assessment_bands(&resolved)
+                .iter()
+                .map(|band| (band.label.as_str(), band.above))
+                .collect::<Vec<_>>(),
+            vec![
+                ("OK", None),
+                ("Could be better", Some(70.0)),
+                ("Needs improvement", Some(80.0)),
+            ]
+        );
+        assert_eq!(
+            assessment_bands(&resolve_for_file(&config, "src/app.ts")).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_default_assessment_bands_follow_thresholds() {
+        let mut config = get_default_config();
+        config.assessment_thresholds = AssessmentThresholds {
+            could_be_better: 30.0,
+            needs_improvement: 45.0,
+        };
+
+        let bands = assessment_bands(&config);
+        assert_eq!(
+            bands
+                .iter()
+                .map(|band| (band.label.as_str(), band.above, band.severity))
+                .collect::<Vec<_>>(),
+            vec![
+                ("OK", None, Severity::None),
+                ("Could be better", Some(30.0), Severity::Warning),
+                ("Needs improvement", Some(45.0), Severity::Error),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_config_with_unknown_field() {
+        let temp_file = create_temp_file(r#"{ "exclude_directorys": ["/baz"] }"#);
+        let path = temp_file.path().to_str().unwrap();
+
+        match read_config(path.to_string(), false) {
+            Err(err @ FtaError::ConfigParse { .. }) => {
+                let message = err.to_string();
+                assert!(
+                    message.starts_with(&format!(
+                        "ConfigError! Invalid config file {} at line 1, column 22: unknown field `exclude_directorys`",
+                        path
+                    )),
+                    "Unexpected message: {}",
+                    message
+                );
+            }
+            config => panic!("Expected a parse error, got {:?}", config),
+        }
+    }
 }
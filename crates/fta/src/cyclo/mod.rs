@@ -3,7 +3,7 @@ use swc_ecma_visit::{Visit, VisitWith};
 
 mod tests;
 
-struct ComplexityVisitor {
+pub struct ComplexityVisitor {
     complexity: usize,
 }
 
@@ -76,3 +76,10 @@ pub fn cyclomatic_complexity(module: &Module) -> usize {
     visitor.visit_module(&module);
     visitor.complexity
 }
+
+/// Cyclomatic complexity of a single node, such as a function or method.
+pub fn node_complexity<N: VisitWith<ComplexityVisitor>>(node: &N) -> usize {
+    let mut visitor = ComplexityVisitor::new();
+    node.visit_with(&mut visitor);
+    visitor.complexity
+}
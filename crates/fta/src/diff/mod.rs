@@ -0,0 +1,208 @@
+use crate::structs::{FileData, HalsteadMetrics};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+
+mod tests;
+
+#[derive(Debug, Clone)]
+pub struct DiffError {
+    message: String,
+}
+
+impl fmt::Display for DiffError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DiffError! {}", self.message)
+    }
+}
+
+/// Reads the files of a report written with `--json` or `--format json`, with or without
+/// `--summary`.
+pub fn read_report(path: &str) -> Result<Vec<FileData>, DiffError> {
+    let invalid_report = |message: &dyn fmt::Display| DiffError {
+        message: format!("Invalid report {}: {}", path, message),
+    };
+    let content = fs::read_to_string(path).map_err(|err| DiffError {
+        message: format!("Unable to read report {}: {}", path, err),
+    })?;
+
+    let files = match serde_json::from_str::<Value>(&content) {
+        Ok(Value::Object(mut report)) => report.remove("files").unwrap_or_default(),
+        Ok(files) => files,
+        Err(err) => return Err(invalid_report(&err)),
+    };
+    if !files.is_array() {
+        return Err(invalid_report(
+            &"expected the JSON output of FTA, a list of files or an object with `files`",
+        ));
+    }
+
+    serde_json::from_value(files).map_err(|err| invalid_report(&err))
+}
+
+/// How much each Halstead metric of a file changed.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct HalsteadDelta {
+    pub uniq_operators: i64,
+    pub uniq_operands: i64,
+    pub total_operators: i64,
+    pub total_operands: i64,
+    pub program_length: i64,
+    pub vocabulary_size: i64,
+    pub volume: f64,
+    pub difficulty: f64,
+    pub effort: f64,
+    pub time: f64,
+    pub bugs: f64,
+}
+
+/// How much the metrics of a file changed, as the new value minus the old one.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct MetricDelta {
+    pub fta_score: f64,
+    pub cyclo: i64,
+    pub cognitive: i64,
+    pub line_count: i64,
+    pub halstead: HalsteadDelta,
+}
+
+/// A file that is in both reports, with different metrics.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct FileDiff {
+    pub file_name: String,
+    pub old_score: f64,
+    pub new_score: f64,
+    pub delta: MetricDelta,
+}
+
+/// A file that is only in one of the reports.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct FileScore {
+    pub file_name: String,
+    pub fta_score: f64,
+}
+
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct ReportDiff {
+    /// Files whose metrics changed, largest increase of the FTA score first.
+    pub changed: Vec<FileDiff>,
+    /// Files that are only in the new report.
+    pub added: Vec<FileScore>,
+    /// Files that are only in the old report.
+    pub removed: Vec<FileScore>,
+    /// The number of files with the same metrics in both reports.
+    pub unchanged_count: usize,
+}
+
+impl ReportDiff {
+    /// Files whose FTA score increased by more than `tolerance`, largest increase first.
+    pub fn regressions(&self, tolerance: f64) -> Vec<&FileDiff> {
+        self.changed
+            .iter()
+            .filter(|file_diff| file_diff.delta.fta_score > tolerance)
+            .collect()
+    }
+}
+
+fn halstead_delta(old: &HalsteadMetrics, new: &HalsteadMetrics) -> HalsteadDelta {
+    let count = |old: usize, new: usize| new as i64 - old as i64;
+
+    HalsteadDelta {
+        uniq_operators: count(old.uniq_operators, new.uniq_operators),
+        uniq_operands: count(old.uniq_operands, new.uniq_operands),
+        total_operators: count(old.total_operators, new.total_operators),
+        total_operands: count(old.total_operands, new.total_operands),
+        program_length: count(old.program_length, new.program_length),
+        vocabulary_size: count(old.vocabulary_size, new.vocabulary_size),
+        volume: new.volume - old.volume,
+        difficulty: new.difficulty - old.difficulty,
+        effort: new.effort - old.effort,
+        time: new.time - old.time,
+        bugs: new.bugs - old.bugs,
+    }
+}
+
+fn file_diff(old: &FileData, new: &FileData) -> FileDiff {
+    FileDiff {
+        file_name: new.file_name.clone(),
+        old_score: old.fta_score,
+        new_score: new.fta_score,
+        delta: MetricDelta {
+            fta_score: new.fta_score - old.fta_score,
+            cyclo: new.cyclo as i64 - old.cyclo as i64,
+            cognitive: new.cognitive as i64 - old.cognitive as i64,
+            line_count: new.line_count as i64 - old.line_count as i64,
+            halstead: halstead_delta(&old.halstead, &new.halstead),
+        },
+    }
+}
+
+/// Matches the files of two reports by name, and compares their metrics.
+pub fn diff_reports(old: &[FileData], new: &[FileData]) -> ReportDiff {
+    let old: BTreeMap<&str, &FileData> = old
+        .iter()
+        .map(|file_data| (file_data.file_name.as_str(), file_data))
+        .collect();
+    let new: BTreeMap<&str, &FileData> = new
+        .iter()
+        .map(|file_data| (file_data.file_name.as_str(), file_data))
+        .collect();
+    let mut diff = ReportDiff::default();
+
+    for (file_name, new_data) in &new {
+        match old.get(file_name) {
+            Some(old_data)
+                if old_data.fta_score == new_data.fta_score
+                    && old_data.cyclo == new_data.cyclo
+                    && old_data.cognitive == new_data.cognitive
+                    && old_data.line_count == new_data.line_count
+                    && old_data.halstead == new_data.halstead =>
+            {
+                diff.unchanged_count += 1;
+            }
+            Some(old_data) => diff.changed.push(file_diff(old_data, new_data)),
+            None => diff.added.push(FileScore {
+                file_name: file_name.to_string(),
+                fta_score: new_data.fta_score,
+            }),
+        }
+    }
+    diff.removed = old
+        .iter()
+        .filter(|(file_name, _)| !new.contains_key(*file_name))
+        .map(|(file_name, old_data)| FileScore {
+            file_name: file_name.to_string(),
+            fta_score: old_data.fta_score,
+        })
+        .collect();
+
+    diff.changed.sort_by(|a, b| {
+        b.delta
+            .fta_score
+            .total_cmp(&a.delta.fta_score)
+            .then_with(|| a.file_name.cmp(&b.file_name))
+    });
+
+    diff
+}
+
+pub fn format_regressions(regressions: &[&FileDiff], tolerance: f64) -> String {
+    let mut message = format!(
+        "RegressionError! {} file(s) got worse by more than {:.2}:",
+        regressions.len(),
+        tolerance
+    );
+    for file_diff in regressions {
+        message.push_str(&format!(
+            "\n  {}: {:.2} -> {:.2} ({:+.2})",
+            file_diff.file_name,
+            file_diff.old_score,
+            file_diff.new_score,
+            file_diff.delta.fta_score
+        ));
+    }
+
+    message
+}
@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod tests {
+    use crate::diff::{diff_reports, format_regressions, read_report};
+    use crate::structs::{test_file_data, FileData};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn file_data(file_name: &str, fta_score: f64, cyclo: usize) -> FileData {
+        FileData {
+            cyclo,
+            ..test_file_data(file_name, fta_score)
+        }
+    }
+
+    #[test]
+    fn test_diff_reports() {
+        let mut grown = file_data("grown.ts", 50.0, 5);
+        grown.line_count = 25;
+        grown.halstead.vocabulary_size = 10;
+        grown.halstead.volume = 20.5;
+        let old = vec![
+            file_data("same.ts", 20.0, 2),
+            file_data("grown.ts", 40.0, 3),
+            file_data("shrunk.ts", 60.0, 8),
+            file_data("slightly.ts", 30.0, 3),
+            file_data("removed.ts", 70.0, 9),
+        ];
+        let new = vec![
+            file_data("added.ts", 35.0, 4),
+            file_data("same.ts", 20.0, 2),
+            grown,
+            file_data("shrunk.ts", 45.0, 6),
+            file_data("slightly.ts", 30.5, 3),
+        ];
+
+        let diff = diff_reports(&old, &new);
+
+        assert_eq!(
+            diff.changed
+                .iter()
+                .map(|file_diff| (file_diff.file_name.as_str(), file_diff.delta.fta_score))
+                .collect::<Vec<_>>(),
+            vec![
+                ("grown.ts", 10.0),
+                ("slightly.ts", 0.5),
+                ("shrunk.ts", -15.0)
+            ]
+        );
+        let grown = &diff.changed[0];
+        assert_eq!((grown.old_score, grown.new_score), (40.0, 50.0));
+        assert_eq!(grown.delta.cyclo, 2);
+        assert_eq!(grown.delta.line_count, 15);
+        assert_eq!(grown.delta.halstead.vocabulary_size, 4);
+        assert_eq!(grown.delta.halstead.volume, 13.5);
+        assert_eq!(diff.changed[2].delta.cyclo, -2);
+
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].file_name, "added.ts");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].fta_score, 70.0);
+        assert_eq!(diff.unchanged_count, 1);
+
+        assert_eq!(diff.regressions(0.0).len(), 2);
+        assert_eq!(diff.regressions(1.0).len(), 1);
+        assert_eq!(
+            format_regressions(&diff.regressions(1.0), 1.0),
+            "RegressionError! 1 file(s) got worse by more than 1.00:\n  grown.ts: 40.00 -> 50.00 (+10.00)"
+        );
+    }
+
+    #[test]
+    fn test_read_report() {
+        let dir = TempDir::new().unwrap();
+        let files = serde_json::to_string(&vec![file_data("a.ts", 20.0, 2)]).unwrap();
+
+        let plain = dir.path().join("plain.json");
+        fs::write(&plain, &files).unwrap();
+        assert_eq!(
+            read_report(plain.to_str().unwrap()).unwrap()[0].file_name,
+            "a.ts"
+        );
+
+        let envelope = dir.path().join("envelope.json");
+        fs::write(
+            &envelope,
+            format!(r#"{{"version":"3.0.0","summary":{{}},"files":{}}}"#, files),
+        )
+        .unwrap();
+        assert_eq!(read_report(envelope.to_str().unwrap()).unwrap().len(), 1);
+
+        // Reports of earlier versions lack the cognitive complexity, severity and functions
+        let legacy = dir.path().join("legacy.json");
+        fs::write(
+            &legacy,
+            r#"[{"file_name":"a.ts","cyclo":2,"halstead":{"uniq_operators":1,"uniq_operands":2,"total_operators":3,"total_operands":4,"program_length":5,"vocabulary_size":6,"volume":7.0,"difficulty":8.0,"effort":9.0,"time":10.0,"bugs":11.0},"line_count":10,"fta_score":20.0,"assessment":"OK"}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            read_report(legacy.to_str().unwrap()).unwrap()[0].cognitive,
+            0
+        );
+
+        let invalid = dir.path().join("invalid.json");
+        fs::write(&invalid, r#"{"score_cap":50}"#).unwrap();
+        assert!(read_report(invalid.to_str().unwrap())
+            .unwrap_err()
+            .to_string()
+            .starts_with("DiffError! Invalid report "));
+        assert!(
+            read_report(dir.path().join("missing.json").to_str().unwrap())
+                .unwrap_err()
+                .to_string()
+                .starts_with("DiffError! Unable to read report ")
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use crate::config::resolve_for_file;
+use crate::structs::{AnalysisReport, FtaConfigResolved};
+use serde::Serialize;
+use std::fmt;
+
+mod tests;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoreCapBreach {
+    pub file_name: String,
+    pub fta_score: f64,
+    /// The score cap of this file, which may differ from the global one due to overrides.
+    pub score_cap: usize,
+}
+
+/// A file that couldn't be parsed as TypeScript or JavaScript, and is missing from the report.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParseFailure {
+    pub file_name: String,
+    /// The message of the first syntax error.
+    pub message: String,
+    /// The one-based line of the syntax error in the original file.
+    pub line: usize,
+    /// The one-based column of the syntax error, in bytes.
+    pub column: usize,
+}
+
+impl fmt::Display for ParseFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ParseError! Unable to parse {} at line {}, column {}: {}",
+            self.file_name, self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseFailure {}
+
+#[derive(Debug)]
+pub enum FtaError {
+    /// The config file couldn't be found or read.
+    ConfigIo { path: String, message: String },
+    /// The config file isn't valid JSON, has unknown fields or values of the wrong type.
+    ConfigParse {
+        path: String,
+        message: String,
+        line: usize,
+        column: usize,
+    },
+    /// One or more files are beyond the score cap. The analysis still ran to completion, so the
+    /// full report is available alongside the offending files.
+    ScoreCapBreached {
+        score_cap: usize,
+        breaches: Vec<ScoreCapBreach>,
+        report: Box<AnalysisReport>,
+    },
+}
+
+impl fmt::Display for FtaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FtaError::ConfigIo { path, message } => {
+                write!(
+                    f,
+                    "ConfigError! Unable to read config file {}: {}",
+                    path, message
+                )
+            }
+            FtaError::ConfigParse {
+                path,
+                message,
+                line,
+                column,
+            } => write!(
+                f,
+                "ConfigError! Invalid config file {} at line {}, column {}: {}",
+                path, line, column, message
+            ),
+            FtaError::ScoreCapBreached {
+                score_cap,
+                breaches,
+                ..
+            } => write!(f, "{}", format_score_cap_breaches(breaches, *score_cap)),
+        }
+    }
+}
+
+impl std::error::Error for FtaError {}
+
+/// Files with a score beyond their score cap, highest score first.
+pub fn find_score_cap_breaches(
+    report: &AnalysisReport,
+    config: &FtaConfigResolved,
+) -> Vec<ScoreCapBreach> {
+    let mut breaches: Vec<ScoreCapBreach> = report
+        .files
+        .iter()
+        .filter_map(|file_data| {
+            let score_cap =
+                file_data.score_cap(resolve_for_file(config, &file_data.file_name).score_cap)?;
+            (file_data.fta_score > score_cap as f64).then(|| ScoreCapBreach {
+                file_name: file_data.file_name.clone(),
+                fta_score: file_data.fta_score,
+                score_cap,
+            })
+        })
+        .collect();
+    breaches.sort_by(|a, b| b.fta_score.partial_cmp(&a.fta_score).unwrap());
+
+    breaches
+}
+
+pub fn format_score_cap_breaches(breaches: &[ScoreCapBreach], score_cap: usize) -> String {
+    let mut message = format!(
+        "ScoreCapError! {} file(s) are beyond the score cap of {}:",
+        breaches.len(),
+        score_cap
+    );
+    for breach in breaches {
+        message.push_str(&format!(
+            "\n  {}: {:.2}",
+            breach.file_name, breach.fta_score
+        ));
+        if breach.score_cap != score_cap {
+            message.push_str(&format!(" (score cap of {})", breach.score_cap));
+        }
+    }
+
+    message
+}
@@ -0,0 +1,154 @@
+#[cfg(test)]
+mod tests {
+    use crate::config::get_default_config;
+    use crate::error::{
+        find_score_cap_breaches, format_score_cap_breaches, FtaError, ParseFailure, ScoreCapBreach,
+    };
+    use crate::structs::{test_file_data, AnalysisReport, ConfigOverride, Suppression};
+
+    #[test]
+    fn test_find_score_cap_breaches() {
+        let report = AnalysisReport {
+            files: vec![
+                test_file_data("a.ts", 50.5),
+                test_file_data("b.ts", 50.0),
+                test_file_data("c.ts", 70.0),
+            ],
+            failures: vec![],
+        };
+
+        let mut config = get_default_config();
+        config.score_cap = 50;
+
+        assert_eq!(
+            find_score_cap_breaches(&report, &config),
+            vec![
+                ScoreCapBreach {
+                    file_name: "c.ts".to_string(),
+                    fta_score: 70.0,
+                    score_cap: 50,
+                },
+                ScoreCapBreach {
+                    file_name: "a.ts".to_string(),
+                    fta_score: 50.5,
+                    score_cap: 50,
+                },
+            ]
+        );
+        assert!(find_score_cap_breaches(&report, &get_default_config()).is_empty());
+    }
+
+    #[test]
+    fn test_find_score_cap_breaches_with_overrides() {
+        let report = AnalysisReport {
+            files: vec![
+                test_file_data("src/app.ts", 55.0),
+                test_file_data("src/generated/client.ts", 85.0),
+                test_file_data("src/generated/types.ts", 95.0),
+            ],
+            failures: vec![],
+        };
+        let mut config = get_default_config();
+        config.score_cap = 50;
+        config.overrides = vec![ConfigOverride {
+            files: vec!["src/generated/**".to_string()],
+            score_cap: Some(90),
+            ..Default::default()
+        }];
+        let breaches = find_score_cap_breaches(&report, &config);
+
+        assert_eq!(
+            format_score_cap_breaches(&breaches, 50),
+            [
+                "ScoreCapError! 2 file(s) are beyond the score cap of 50:",
+                "  src/generated/types.ts: 95.00 (score cap of 90)",
+                "  src/app.ts: 55.00",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_find_score_cap_breaches_with_suppressions() {
+        let mut capped = test_file_data("parser.ts", 85.0);
+        capped.suppression = Some(Suppression::ScoreCap {
+            line: 1,
+            score_cap: 80,
+            reason: None,
+        });
+        let mut ignored = test_file_data("generated.ts", 150.0);
+        ignored.suppression = Some(Suppression::IgnoreFile {
+            line: 1,
+            reason: Some("generated".to_string()),
+        });
+        let mut within_cap = test_file_data("machine.ts", 75.0);
+        within_cap.suppression = Some(Suppression::ScoreCap {
+            line: 3,
+            score_cap: 80,
+            reason: None,
+        });
+        let report = AnalysisReport {
+            files: vec![capped, ignored, within_cap],
+            failures: vec![],
+        };
+        let mut config = get_default_config();
+        config.score_cap = 50;
+
+        assert_eq!(
+            find_score_cap_breaches(&report, &config),
+            vec![ScoreCapBreach {
+                file_name: "parser.ts".to_string(),
+                fta_score: 85.0,
+                score_cap: 80,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_score_cap_breached_lists_every_file() {
+        let report = AnalysisReport {
+            files: vec![test_file_data("a.ts", 70.0), test_file_data("b.ts", 65.25)],
+            failures: vec![],
+        };
+        let mut config = get_default_config();
+        config.score_cap = 60;
+        let error = FtaError::ScoreCapBreached {
+            score_cap: 60,
+            breaches: find_score_cap_breaches(&report, &config),
+            report: Box::new(report),
+        };
+
+        assert_eq!(
+            error.to_string(),
+            [
+                "ScoreCapError! 2 file(s) are beyond the score cap of 60:",
+                "  a.ts: 70.00",
+                "  b.ts: 65.25",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let error = FtaError::ConfigIo {
+            path: "fta.json".to_string(),
+            message: "No such file or directory".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "ConfigError! Unable to read config file fta.json: No such file or directory"
+        );
+
+        let failure = ParseFailure {
+            file_name: "a.ts".to_string(),
+            message: "Expression expected".to_string(),
+            line: 3,
+            column: 14,
+        };
+        assert_eq!(
+            failure.to_string(),
+            "ParseError! Unable to parse a.ts at line 3, column 14: Expression expected"
+        );
+    }
+}
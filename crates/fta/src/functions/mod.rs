@@ -0,0 +1,221 @@
+use crate::calculate_fta_score;
+use crate::cognitive::{node_cognitive_complexity, CognitiveVisitor};
+use crate::cyclo::{node_complexity, ComplexityVisitor};
+use crate::halstead::{analyze_node, AstAnalyzer};
+use crate::parse::LineMap;
+use crate::structs::FunctionData;
+use swc_common::Span;
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+mod tests;
+
+const ANONYMOUS: &str = "<anonymous>";
+
+/// Collects every function-like scope in a module: function declarations and expressions,
+/// arrow functions, class methods, constructors, getters/setters and object methods.
+///
+/// Each function is measured over its whole body, so nested functions also contribute to the
+/// metrics of the function that contains them (as well as being reported on their own).
+struct FunctionCollector<'a> {
+    line_map: &'a LineMap,
+    class_names: Vec<String>,
+    pending_name: Option<String>,
+    functions: Vec<FunctionData>,
+}
+
+impl<'a> FunctionCollector<'a> {
+    fn new(line_map: &'a LineMap) -> Self {
+        FunctionCollector {
+            line_map,
+            class_names: Vec::new(),
+            pending_name: None,
+            functions: Vec::new(),
+        }
+    }
+
+    fn record<N>(&mut self, name: String, span: Span, node: &N)
+    where
+        N: VisitWith<ComplexityVisitor> + VisitWith<CognitiveVisitor> + VisitWith<AstAnalyzer>,
+    {
+        let cyclo = node_complexity(node);
+        let cognitive = node_cognitive_complexity(node, recursion_name(&name));
+        let halstead = analyze_node(node);
+        let line_count = self.line_map.code_line(span.hi) - self.line_map.code_line(span.lo) + 1;
+        let fta_score = calculate_fta_score(line_count, cyclo, halstead.vocabulary_size);
+
+        self.functions.push(FunctionData {
+            name,
+            start_line: self.line_map.original_line(span.lo),
+            end_line: self.line_map.original_line(span.hi),
+            cyclo,
+            cognitive,
+            halstead,
+            line_count,
+            fta_score,
+            suppression: None,
+        });
+    }
+
+    /// Names a function after the binding it is assigned to, e.g. `const foo = () => {}`.
+    /// The name is only handed to the value when it is a function or class.
+    fn visit_named_value(&mut self, name: String, value: &Expr) {
+        let previous = self.pending_name.take();
+        if is_nameable(value) {
+            self.pending_name = Some(name);
+        }
+        value.visit_with(self);
+        self.pending_name = previous;
+    }
+
+    fn take_name(&mut self, ident: Option<&Ident>) -> String {
+        let pending = self.pending_name.take();
+        match ident {
+            Some(ident) => ident.sym.to_string(),
+            None => pending.unwrap_or_else(|| ANONYMOUS.to_string()),
+        }
+    }
+
+    fn member_name(&self, key: String) -> String {
+        match self.class_names.last() {
+            Some(class_name) => format!("{}.{}", class_name, key),
+            None => key,
+        }
+    }
+
+    fn visit_class_body(&mut self, name: String, class: &Class) {
+        self.class_names.push(name);
+        class.visit_children_with(self);
+        self.class_names.pop();
+    }
+}
+
+fn is_nameable(expr: &Expr) -> bool {
+    match expr {
+        Expr::Arrow(_) | Expr::Fn(_) | Expr::Class(_) => true,
+        Expr::Paren(paren) => is_nameable(&paren.expr),
+        _ => false,
+    }
+}
+
+/// The name a function would be called by if it recursed, if it's a plain identifier.
+fn recursion_name(name: &str) -> Option<&str> {
+    let is_identifier = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    is_identifier.then_some(name)
+}
+
+fn prop_name(key: &PropName) -> String {
+    match key {
+        PropName::Ident(ident) => ident.sym.to_string(),
+        PropName::Str(str_lit) => str_lit.value.to_string(),
+        PropName::Num(num) => num.value.to_string(),
+        PropName::BigInt(big_int) => big_int.value.to_string(),
+        PropName::Computed(_) => "[computed]".to_string(),
+    }
+}
+
+fn method_name(key: String, kind: MethodKind) -> String {
+    match kind {
+        MethodKind::Method => key,
+        MethodKind::Getter => format!("get {}", key),
+        MethodKind::Setter => format!("set {}", key),
+    }
+}
+
+impl Visit for FunctionCollector<'_> {
+    fn visit_fn_decl(&mut self, node: &FnDecl) {
+        self.pending_name = None;
+        self.record(
+            node.ident.sym.to_string(),
+            node.function.span,
+            &*node.function,
+        );
+        node.function.visit_children_with(self);
+    }
+
+    fn visit_fn_expr(&mut self, node: &FnExpr) {
+        let name = self.take_name(node.ident.as_ref());
+        self.record(name, node.function.span, &*node.function);
+        node.function.visit_children_with(self);
+    }
+
+    fn visit_arrow_expr(&mut self, node: &ArrowExpr) {
+        let name = self.take_name(None);
+        self.record(name, node.span, node);
+        node.visit_children_with(self);
+    }
+
+    fn visit_class_decl(&mut self, node: &ClassDecl) {
+        self.pending_name = None;
+        self.visit_class_body(node.ident.sym.to_string(), &node.class);
+    }
+
+    fn visit_class_expr(&mut self, node: &ClassExpr) {
+        let name = self.take_name(node.ident.as_ref());
+        self.visit_class_body(name, &node.class);
+    }
+
+    fn visit_class_method(&mut self, node: &ClassMethod) {
+        let name = self.member_name(method_name(prop_name(&node.key), node.kind));
+        self.record(name, node.span, &*node.function);
+        node.function.visit_children_with(self);
+    }
+
+    fn visit_private_method(&mut self, node: &PrivateMethod) {
+        let key = format!("#{}", node.key.name);
+        let name = self.member_name(method_name(key, node.kind));
+        self.record(name, node.span, &*node.function);
+        node.function.visit_children_with(self);
+    }
+
+    fn visit_constructor(&mut self, node: &Constructor) {
+        let name = self.member_name("constructor".to_string());
+        self.record(name, node.span, node);
+        node.visit_children_with(self);
+    }
+
+    fn visit_class_prop(&mut self, node: &ClassProp) {
+        if let Some(value) = &node.value {
+            let name = self.member_name(prop_name(&node.key));
+            self.visit_named_value(name, value);
+        }
+    }
+
+    fn visit_method_prop(&mut self, node: &MethodProp) {
+        self.record(prop_name(&node.key), node.function.span, &*node.function);
+        node.function.visit_children_with(self);
+    }
+
+    fn visit_getter_prop(&mut self, node: &GetterProp) {
+        self.record(format!("get {}", prop_name(&node.key)), node.span, node);
+        node.visit_children_with(self);
+    }
+
+    fn visit_setter_prop(&mut self, node: &SetterProp) {
+        self.record(format!("set {}", prop_name(&node.key)), node.span, node);
+        node.visit_children_with(self);
+    }
+
+    fn visit_key_value_prop(&mut self, node: &KeyValueProp) {
+        self.visit_named_value(prop_name(&node.key), &node.value);
+    }
+
+    fn visit_var_declarator(&mut self, node: &VarDeclarator) {
+        match (&node.name, &node.init) {
+            (Pat::Ident(binding), Some(init)) => {
+                self.visit_named_value(binding.id.sym.to_string(), init);
+            }
+            _ => node.visit_children_with(self),
+        }
+    }
+}
+
+/// Analyzes every function in the module individually, in source order.
+pub fn analyze_functions(module: &Module, line_map: &LineMap) -> Vec<FunctionData> {
+    let mut collector = FunctionCollector::new(line_map);
+    module.visit_with(&mut collector);
+    collector.functions
+}
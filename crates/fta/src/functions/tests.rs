@@ -0,0 +1,205 @@
+#[cfg(test)]
+mod tests {
+    use crate::functions::analyze_functions;
+    use crate::parse::{parse_module, LineMap};
+    use crate::structs::FunctionData;
+
+    fn analyze(ts_code: &str) -> Vec<FunctionData> {
+        match parse_module(ts_code, true, false) {
+            (Ok(module), _line_count) => analyze_functions(&module, &LineMap::new(ts_code)),
+            (Err(_err), _) => {
+                panic!("failed");
+            }
+        }
+    }
+
+    fn names(functions: &[FunctionData]) -> Vec<&str> {
+        functions
+            .iter()
+            .map(|function| function.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_no_functions() {
+        let ts_code = r#"
+            const x = 1;
+            console.log(x);
+        "#;
+        assert!(analyze(ts_code).is_empty());
+    }
+
+    #[test]
+    fn test_function_declaration() {
+        let ts_code = r#"
+            function add(a: number, b: number): number {
+                if (a > b) {
+                    return a + b;
+                }
+                return b + a;
+            }
+        "#;
+        let functions = analyze(ts_code);
+
+        assert_eq!(names(&functions), vec!["add"]);
+        assert_eq!(functions[0].start_line, 2);
+        assert_eq!(functions[0].end_line, 7);
+        assert_eq!(functions[0].line_count, 6);
+        assert_eq!(functions[0].cyclo, 2);
+        assert!(functions[0].fta_score > 0.0);
+    }
+
+    #[test]
+    fn test_line_numbers_account_for_empty_lines() {
+        let ts_code = r#"
+            const a = 1;
+
+            const b = () => {
+
+                return a;
+            };
+        "#;
+        let functions = analyze(ts_code);
+
+        assert_eq!(names(&functions), vec!["b"]);
+        assert_eq!(functions[0].start_line, 4);
+        assert_eq!(functions[0].end_line, 7);
+        assert_eq!(functions[0].line_count, 3);
+    }
+
+    #[test]
+    fn test_named_function_expressions_and_arrows() {
+        let ts_code = r#"
+            const first = function () {};
+            const second = function inner() {};
+            let third = (x) => x * 2;
+            [1, 2].map((x) => x + 1);
+        "#;
+        let functions = analyze(ts_code);
+
+        assert_eq!(
+            names(&functions),
+            vec!["first", "inner", "third", "<anonymous>"]
+        );
+    }
+
+    #[test]
+    fn test_class_members() {
+        let ts_code = r#"
+            class Counter {
+                #count = 0;
+                constructor(start: number) {
+                    this.#count = start;
+                }
+                get value() {
+                    return this.#count;
+                }
+                set value(next: number) {
+                    this.#count = next;
+                }
+                increment() {
+                    this.#count++;
+                }
+                #reset() {
+                    this.#count = 0;
+                }
+                handle = () => {
+                    this.increment();
+                };
+            }
+        "#;
+        let functions = analyze(ts_code);
+
+        assert_eq!(
+            names(&functions),
+            vec![
+                "Counter.constructor",
+                "Counter.get value",
+                "Counter.set value",
+                "Counter.increment",
+                "Counter.#reset",
+                "Counter.handle",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_object_methods() {
+        let ts_code = r#"
+            const api = {
+                fetch() {
+                    return 1;
+                },
+                get ready() {
+                    return true;
+                },
+                parse: (input) => input,
+                value: 1,
+            };
+        "#;
+        let functions = analyze(ts_code);
+
+        assert_eq!(names(&functions), vec!["fetch", "get ready", "parse"]);
+    }
+
+    #[test]
+    fn test_nested_functions_are_reported_separately() {
+        let ts_code = r#"
+            function outer(items) {
+                return items.filter((item) => {
+                    if (item) {
+                        return true;
+                    }
+                    return false;
+                });
+            }
+        "#;
+        let functions = analyze(ts_code);
+
+        assert_eq!(names(&functions), vec!["outer", "<anonymous>"]);
+        assert_eq!(functions[0].cyclo, 2);
+        assert_eq!(functions[1].cyclo, 2);
+        assert_eq!(functions[1].start_line, 3);
+        assert_eq!(functions[1].end_line, 8);
+    }
+
+    #[test]
+    fn test_more_complex_functions_score_higher() {
+        let ts_code = r#"
+            function simple() {
+                return 1;
+            }
+            function complex(a, b, c) {
+                if (a && b) {
+                    for (let i = 0; i < c; i++) {
+                        if (i % 2 === 0 || i % 3 === 0) {
+                            console.log(i);
+                        } else {
+                            console.warn(i);
+                        }
+                    }
+                }
+                return a ? b : c;
+            }
+        "#;
+        let functions = analyze(ts_code);
+
+        assert_eq!(names(&functions), vec!["simple", "complex"]);
+        assert!(functions[1].fta_score > functions[0].fta_score);
+        assert_eq!(functions[0].cognitive, 0);
+        assert_eq!(functions[1].cognitive, 10);
+    }
+
+    #[test]
+    fn test_recursion_counts_towards_cognitive_complexity() {
+        let ts_code = r#"
+            function factorial(n) {
+                return n <= 1 ? 1 : n * factorial(n - 1);
+            }
+        "#;
+        let functions = analyze(ts_code);
+
+        assert_eq!(names(&functions), vec!["factorial"]);
+        assert_eq!(functions[0].cognitive, 2);
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+mod tests;
+
+#[derive(Debug, Clone)]
+pub struct GitError {
+    message: String,
+}
+
+impl fmt::Display for GitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "GitError! {}", self.message)
+    }
+}
+
+/// Runs a git command inside `repo_path` and returns its stdout.
+fn run_git(repo_path: &str, args: &[&str]) -> Result<String, GitError> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_path)
+        .output()
+        .map_err(|err| GitError {
+            message: format!("Unable to run git in {}: {}", repo_path, err),
+        })?;
+
+    if !output.status.success() {
+        return Err(GitError {
+            message: format!(
+                "`git {}` failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Lists the files touched by a `git diff`, relative to `repo_path`. Deleted files are left out
+/// as there is nothing left to analyze.
+fn diff_file_names(repo_path: &str, diff_args: &[&str]) -> Result<HashSet<PathBuf>, GitError> {
+    let mut args = vec!["diff", "--name-only", "-z", "--relative", "--diff-filter=d"];
+    args.extend_from_slice(diff_args);
+    let stdout = run_git(repo_path, &args)?;
+
+    Ok(file_names(&stdout))
+}
+
+/// Splits the NUL-separated output of `-z` into paths.
+fn file_names(stdout: &str) -> HashSet<PathBuf> {
+    stdout
+        .split('\0')
+        .filter(|file_name| !file_name.is_empty())
+        .map(|file_name| Path::new(file_name).to_path_buf())
+        .collect()
+}
+
+/// Files that differ between `git_ref` and the working tree, including uncommitted changes and
+/// new files that haven't been added yet.
+pub fn changed_since(repo_path: &str, git_ref: &str) -> Result<HashSet<PathBuf>, GitError> {
+    let mut changed = diff_file_names(repo_path, &[git_ref, "--"])?;
+
+    // Untracked files are listed relative to `repo_path`, like the diff
+    let untracked = run_git(
+        repo_path,
+        &["ls-files", "--others", "--exclude-standard", "-z"],
+    )?;
+    changed.extend(file_names(&untracked));
+
+    Ok(changed)
+}
+
+/// Files with changes staged for the next commit, for use in pre-commit hooks.
+pub fn staged(repo_path: &str) -> Result<HashSet<PathBuf>, GitError> {
+    diff_file_names(repo_path, &["--cached"])
+}
+
+/// A commit on the first-parent history of `HEAD`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Revision {
+    pub commit: String,
+    /// The commit date, as `YYYY-MM-DD`.
+    pub date: String,
+}
+
+/// Every `step`th commit since `since` on the first-parent history of `HEAD`, oldest first. The
+/// most recent commit is always included, and counting back from it, so that repeated runs line
+/// up as new commits are added.
+pub fn revisions(repo_path: &str, since: &str, step: usize) -> Result<Vec<Revision>, GitError> {
+    let since = format!("--since={}", since);
+    let stdout = run_git(
+        repo_path,
+        &["log", "--first-parent", &since, "--format=%H%x1f%cs"],
+    )?;
+
+    let mut revisions: Vec<Revision> = stdout
+        .lines()
+        .step_by(step.max(1))
+        .filter_map(|line| line.split_once('\x1f'))
+        .map(|(commit, date)| Revision {
+            commit: commit.to_string(),
+            date: date.to_string(),
+        })
+        .collect();
+    revisions.reverse();
+
+    Ok(revisions)
+}
+
+/// A temporary, detached checkout of the repository of `repo_path`, which leaves the working tree
+/// alone. It's removed again when dropped.
+pub struct Worktree {
+    repo_path: String,
+    dir: PathBuf,
+    /// The path of the project within the repository, e.g. `packages/app/`.
+    prefix: String,
+}
+
+impl Worktree {
+    /// Adds a worktree in `dir`, which must not exist or be empty.
+    pub fn add(repo_path: &str, dir: &Path) -> Result<Worktree, GitError> {
+        let prefix = run_git(repo_path, &["rev-parse", "--show-prefix"])?;
+        let dir_arg = dir.to_string_lossy();
+        run_git(
+            repo_path,
+            &["worktree", "add", "--detach", "--quiet", &dir_arg, "HEAD"],
+        )?;
+
+        Ok(Worktree {
+            repo_path: repo_path.to_string(),
+            dir: dir.to_path_buf(),
+            prefix: prefix.trim().to_string(),
+        })
+    }
+
+    pub fn checkout(&self, commit: &str) -> Result<(), GitError> {
+        run_git(
+            &self.dir.to_string_lossy(),
+            &["checkout", "--detach", "--quiet", "--force", commit],
+        )?;
+        Ok(())
+    }
+
+    /// The path of the project within the checkout, which may not exist at every commit.
+    pub fn project_path(&self) -> PathBuf {
+        self.dir.join(&self.prefix)
+    }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        let dir_arg = self.dir.to_string_lossy();
+        if let Err(err) = run_git(
+            &self.repo_path,
+            &["worktree", "remove", "--force", &dir_arg],
+        ) {
+            log::warn!("{}", err);
+        }
+    }
+}
+
+/// How often a file was changed, and by whom, within a window of git history.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileHistory {
+    pub commits: usize,
+    /// The email addresses of the authors of the commits.
+    pub authors: BTreeSet<String>,
+    /// The date of the most recent commit, as `YYYY-MM-DD`.
+    pub last_modified: String,
+}
+
+/// The history of every file committed since `since`, which is any date git understands, such as
+/// `2024-01-01` or `6 months ago`. Paths are relative to `repo_path`, and merge commits are left
+/// out as their changes are already counted in the commits they merge.
+pub fn file_histories(
+    repo_path: &str,
+    since: &str,
+) -> Result<HashMap<PathBuf, FileHistory>, GitError> {
+    let since = format!("--since={}", since);
+    let stdout = run_git(
+        repo_path,
+        &[
+            "-c",
+            "core.quotePath=false",
+            "log",
+            "--no-merges",
+            &since,
+            "--name-only",
+            "--relative",
+            "--format=%x1e%aE%x1f%as",
+        ],
+    )?;
+
+    let mut histories: HashMap<PathBuf, FileHistory> = HashMap::new();
+    for commit in stdout.split('\x1e').filter(|commit| !commit.is_empty()) {
+        let mut lines = commit.lines();
+        let (author, date) = lines
+            .next()
+            .and_then(|header| header.split_once('\x1f'))
+            .unwrap_or_default();
+
+        for file_name in lines.filter(|line| !line.is_empty()) {
+            let history = histories.entry(PathBuf::from(file_name)).or_default();
+            history.commits += 1;
+            history.authors.insert(author.to_string());
+            if *date > *history.last_modified {
+                history.last_modified = date.to_string();
+            }
+        }
+    }
+
+    Ok(histories)
+}
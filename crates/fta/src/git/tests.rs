@@ -0,0 +1,143 @@
+#[cfg(test)]
+mod tests {
+    use crate::git::{changed_since, file_histories, staged};
+    use std::collections::{BTreeSet, HashSet};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_AUTHOR_NAME", "fta")
+            .env("GIT_AUTHOR_EMAIL", "fta@example.com")
+            .env("GIT_COMMITTER_NAME", "fta")
+            .env("GIT_COMMITTER_EMAIL", "fta@example.com")
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    fn create_repo() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/a.ts"), "const a = 1;").unwrap();
+        fs::write(dir.path().join("src/b.ts"), "const b = 1;").unwrap();
+        fs::write(dir.path().join("src/c.ts"), "const c = 1;").unwrap();
+        git(dir.path(), &["add", "-A"]);
+        git(dir.path(), &["commit", "-q", "-m", "initial"]);
+        dir
+    }
+
+    fn paths(file_names: &[&str]) -> HashSet<PathBuf> {
+        file_names.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn test_changed_since() {
+        let dir = create_repo();
+        fs::write(dir.path().join("src/a.ts"), "const a = 2;").unwrap();
+        fs::write(dir.path().join("src/new.ts"), "const n = 1;").unwrap();
+        fs::remove_file(dir.path().join("src/c.ts")).unwrap();
+        git(dir.path(), &["add", "src/new.ts"]);
+        git(dir.path(), &["commit", "-q", "-m", "second"]);
+        fs::write(dir.path().join("src/b.ts"), "const b = 2;").unwrap();
+        fs::write(dir.path().join("src/untracked.ts"), "const u = 1;").unwrap();
+        fs::write(dir.path().join(".gitignore"), "src/ignored.ts\n").unwrap();
+        fs::write(dir.path().join("src/ignored.ts"), "const i = 1;").unwrap();
+
+        let repo_path = dir.path().to_str().unwrap();
+        let changed = changed_since(repo_path, "HEAD~1").unwrap();
+
+        // Deleted and ignored files are skipped, uncommitted changes and new files are included
+        assert_eq!(
+            changed,
+            paths(&[
+                ".gitignore",
+                "src/a.ts",
+                "src/b.ts",
+                "src/new.ts",
+                "src/untracked.ts"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_changed_since_is_relative_to_the_project() {
+        let dir = create_repo();
+        fs::write(dir.path().join("src/a.ts"), "const a = 2;").unwrap();
+        fs::write(dir.path().join("src/new.ts"), "const n = 1;").unwrap();
+        fs::write(dir.path().join("outside.ts"), "const o = 1;").unwrap();
+
+        let project_path = dir.path().join("src");
+        let changed = changed_since(project_path.to_str().unwrap(), "HEAD").unwrap();
+
+        assert_eq!(changed, paths(&["a.ts", "new.ts"]));
+    }
+
+    #[test]
+    fn test_staged() {
+        let dir = create_repo();
+        fs::write(dir.path().join("src/a.ts"), "const a = 2;").unwrap();
+        fs::write(dir.path().join("src/b.ts"), "const b = 2;").unwrap();
+        git(dir.path(), &["add", "src/a.ts"]);
+
+        let staged = staged(dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(staged, paths(&["src/a.ts"]));
+    }
+
+    #[test]
+    fn test_file_histories() {
+        let dir = create_repo();
+        fs::write(dir.path().join("src/a.ts"), "const a = 2;").unwrap();
+        git(dir.path(), &["commit", "-q", "-am", "second"]);
+        fs::write(dir.path().join("src/a.ts"), "const a = 3;").unwrap();
+        fs::write(dir.path().join("src/ö.ts"), "const ö = 1;").unwrap();
+        git(dir.path(), &["add", "-A"]);
+        git(
+            dir.path(),
+            &[
+                "commit",
+                "-q",
+                "-m",
+                "third",
+                "--author",
+                "Other <other@example.com>",
+            ],
+        );
+
+        let project_path = dir.path().join("src");
+        let histories = file_histories(project_path.to_str().unwrap(), "1 year ago").unwrap();
+
+        let a = &histories[&PathBuf::from("a.ts")];
+        assert_eq!(a.commits, 3);
+        assert_eq!(
+            a.authors,
+            BTreeSet::from([
+                "fta@example.com".to_string(),
+                "other@example.com".to_string()
+            ])
+        );
+        assert_eq!(a.last_modified.len(), "2024-01-01".len());
+        assert_eq!(histories[&PathBuf::from("b.ts")].commits, 1);
+        assert_eq!(histories[&PathBuf::from("ö.ts")].commits, 1);
+
+        // Commits before the window aren't counted
+        assert!(file_histories(project_path.to_str().unwrap(), "2099-12-31")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_unknown_ref() {
+        let dir = create_repo();
+        let result = changed_since(dir.path().to_str().unwrap(), "does-not-exist");
+
+        assert!(result.unwrap_err().to_string().starts_with("GitError! "));
+    }
+}
@@ -0,0 +1,142 @@
+use crate::structs::FileData;
+use crate::utils::{median, percentile};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+mod tests;
+
+/// How files are rolled up into groups.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupBy {
+    /// The directory of each file, optionally truncated to the first `depth` directories.
+    Directory { depth: Option<usize> },
+    /// The nearest directory containing a `package.json`.
+    Package,
+}
+
+impl FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once(':') {
+            None if value == "directory" => Ok(GroupBy::Directory { depth: None }),
+            None if value == "package" => Ok(GroupBy::Package),
+            Some(("directory", depth)) => match depth.parse::<usize>() {
+                Ok(depth) if depth > 0 => Ok(GroupBy::Directory { depth: Some(depth) }),
+                _ => Err("the depth must be a positive number".to_string()),
+            },
+            _ => Err("expected `directory`, `directory:<depth>` or `package`".to_string()),
+        }
+    }
+}
+
+/// Aggregate metrics of the files in a directory or package.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct GroupData {
+    pub group: String,
+    pub file_count: usize,
+    pub line_count: usize,
+    pub mean_score: f64,
+    pub median_score: f64,
+    pub p90_score: f64,
+    pub max_score: f64,
+    /// The summed Halstead effort of the files.
+    pub effort: f64,
+    /// The summed Halstead bugs estimate of the files.
+    pub bugs: f64,
+}
+
+fn directory_group(file_name: &str, depth: Option<usize>) -> String {
+    let file_name = file_name.replace('\\', "/");
+    let mut directories: Vec<&str> = file_name.split('/').collect();
+    directories.pop();
+    if let Some(depth) = depth {
+        directories.truncate(depth);
+    }
+
+    if directories.is_empty() {
+        ".".to_string()
+    } else {
+        directories.join("/")
+    }
+}
+
+/// The name of the package in `directory`, if it has a `package.json`. Packages without a name are
+/// identified by their directory instead.
+fn read_package_name(repo_path: &str, directory: &Path) -> Option<String> {
+    let manifest =
+        fs::read_to_string(Path::new(repo_path).join(directory).join("package.json")).ok()?;
+    let name = serde_json::from_str::<serde_json::Value>(&manifest)
+        .ok()
+        .and_then(|manifest| manifest["name"].as_str().map(str::to_string));
+
+    Some(name.unwrap_or_else(|| match directory.to_str() {
+        Some("") | None => ".".to_string(),
+        Some(directory) => directory.replace('\\', "/"),
+    }))
+}
+
+fn package_group(
+    repo_path: &str,
+    file_name: &str,
+    packages: &mut HashMap<PathBuf, Option<String>>,
+) -> String {
+    let mut directory = Path::new(file_name).parent();
+    while let Some(current) = directory {
+        let package = packages
+            .entry(current.to_path_buf())
+            .or_insert_with(|| read_package_name(repo_path, current));
+        if let Some(package) = package {
+            return package.clone();
+        }
+        directory = current.parent();
+    }
+
+    ".".to_string()
+}
+
+fn aggregate(group: String, files: &[&FileData]) -> GroupData {
+    let mut scores: Vec<f64> = files.iter().map(|file_data| file_data.fta_score).collect();
+    scores.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    GroupData {
+        group,
+        file_count: files.len(),
+        line_count: files.iter().map(|file_data| file_data.line_count).sum(),
+        mean_score: scores.iter().sum::<f64>() / scores.len() as f64,
+        median_score: median(&scores),
+        p90_score: percentile(&scores, 90.0),
+        max_score: scores[scores.len() - 1],
+        effort: files
+            .iter()
+            .map(|file_data| file_data.halstead.effort)
+            .sum(),
+        bugs: files.iter().map(|file_data| file_data.halstead.bugs).sum(),
+    }
+}
+
+/// Rolls the analyzed files up into groups, highest mean score first. File names are relative to
+/// `repo_path`, which is only read to find the `package.json` of each package.
+pub fn group_files(repo_path: &str, files: &[FileData], group_by: GroupBy) -> Vec<GroupData> {
+    let mut packages = HashMap::new();
+    let mut groups: BTreeMap<String, Vec<&FileData>> = BTreeMap::new();
+
+    for file_data in files {
+        let group = match group_by {
+            GroupBy::Directory { depth } => directory_group(&file_data.file_name, depth),
+            GroupBy::Package => package_group(repo_path, &file_data.file_name, &mut packages),
+        };
+        groups.entry(group).or_default().push(file_data);
+    }
+
+    let mut groups: Vec<GroupData> = groups
+        .into_iter()
+        .map(|(group, files)| aggregate(group, &files))
+        .collect();
+    groups.sort_by(|a, b| b.mean_score.partial_cmp(&a.mean_score).unwrap());
+
+    groups
+}
@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use crate::group::{group_files, GroupBy, GroupData};
+    use crate::structs::{test_file_data, FileData, HalsteadMetrics};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn file_data(file_name: &str, line_count: usize, fta_score: f64) -> FileData {
+        let file_data = test_file_data(file_name, fta_score);
+        FileData {
+            halstead: HalsteadMetrics {
+                effort: 100.0,
+                bugs: 0.5,
+                ..file_data.halstead
+            },
+            line_count,
+            ..file_data
+        }
+    }
+
+    fn group_names(groups: &[GroupData]) -> Vec<(&str, usize)> {
+        groups
+            .iter()
+            .map(|group| (group.group.as_str(), group.file_count))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_group_by() {
+        assert_eq!(
+            "directory".parse::<GroupBy>(),
+            Ok(GroupBy::Directory { depth: None })
+        );
+        assert_eq!(
+            "directory:2".parse::<GroupBy>(),
+            Ok(GroupBy::Directory { depth: Some(2) })
+        );
+        assert_eq!("package".parse::<GroupBy>(), Ok(GroupBy::Package));
+        assert!("directory:0".parse::<GroupBy>().is_err());
+        assert!("directory:two".parse::<GroupBy>().is_err());
+        assert!("package:1".parse::<GroupBy>().is_err());
+        assert!("folder".parse::<GroupBy>().is_err());
+    }
+
+    #[test]
+    fn test_group_by_directory() {
+        let files = vec![
+            file_data("index.ts", 10, 20.0),
+            file_data("src/app.ts", 10, 30.0),
+            file_data("src/features/cart/cart.ts", 10, 60.0),
+            file_data("src\\features\\cart\\checkout.ts", 10, 80.0),
+            file_data("src/features/search/search.ts", 10, 40.0),
+        ];
+
+        assert_eq!(
+            group_names(&group_files("", &files, GroupBy::Directory { depth: None })),
+            vec![
+                ("src/features/cart", 2),
+                ("src/features/search", 1),
+                ("src", 1),
+                (".", 1),
+            ]
+        );
+        assert_eq!(
+            group_names(&group_files(
+                "",
+                &files,
+                GroupBy::Directory { depth: Some(2) }
+            )),
+            vec![("src/features", 3), ("src", 1), (".", 1)]
+        );
+    }
+
+    #[test]
+    fn test_group_statistics() {
+        let files: Vec<FileData> = [10.0, 50.0, 20.0, 40.0, 30.0, 60.0, 70.0, 80.0, 90.0, 100.0]
+            .iter()
+            .map(|score| file_data(&format!("src/{}.ts", score), 15, *score))
+            .collect();
+
+        assert_eq!(
+            group_files("", &files, GroupBy::Directory { depth: None }),
+            vec![GroupData {
+                group: "src".to_string(),
+                file_count: 10,
+                line_count: 150,
+                mean_score: 55.0,
+                median_score: 55.0,
+                p90_score: 90.0,
+                max_score: 100.0,
+                effort: 1000.0,
+                bugs: 5.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_group_by_package() {
+        let dir = TempDir::new().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write("package.json", r#"{ "name": "monorepo" }"#);
+        write("packages/ui/package.json", r#"{ "name": "@acme/ui" }"#);
+        write("packages/utils/package.json", r#"{ "private": true }"#);
+        let repo_path = dir.path().to_str().unwrap();
+
+        let files = vec![
+            file_data("scripts/build.ts", 10, 10.0),
+            file_data("packages/ui/src/button.tsx", 10, 50.0),
+            file_data("packages/ui/src/forms/input.tsx", 10, 70.0),
+            file_data("packages/utils/src/strings.ts", 10, 30.0),
+        ];
+
+        assert_eq!(
+            group_names(&group_files(repo_path, &files, GroupBy::Package)),
+            vec![("@acme/ui", 2), ("packages/utils", 1), ("monorepo", 1)]
+        );
+    }
+
+    #[test]
+    fn test_group_by_package_without_package_json() {
+        let dir = TempDir::new().unwrap();
+        let repo_path = dir.path().to_str().unwrap();
+
+        assert_eq!(
+            group_names(&group_files(
+                repo_path,
+                &[file_data("src/app.ts", 10, 10.0)],
+                GroupBy::Package
+            )),
+            vec![(".", 1)]
+        );
+    }
+}
@@ -7,7 +7,7 @@ use swc_ecma_visit::{Visit, VisitWith};
 mod tests;
 
 #[derive(Debug)]
-struct AstAnalyzer {
+pub struct AstAnalyzer {
     unique_operators: HashSet<String>,
     unique_operands: HashSet<String>,
     total_operators: usize,
@@ -445,8 +445,13 @@ impl HalsteadMetrics {
 }
 
 pub fn analyze_module(module: &Module) -> HalsteadMetrics {
+    analyze_node(module)
+}
+
+/// Halstead metrics of a single node, such as a function or method.
+pub fn analyze_node<N: VisitWith<AstAnalyzer>>(node: &N) -> HalsteadMetrics {
     let mut analyzer = AstAnalyzer::new();
-    module.visit_with(&mut analyzer);
+    node.visit_with(&mut analyzer);
 
     // Useful for debugging (but very verbose):
     // println!("unique operators: {:?}", analyzer.unique_operators);
@@ -0,0 +1,118 @@
+use crate::error::FtaError;
+use crate::git::{revisions, GitError, Revision, Worktree};
+use crate::structs::{FileData, FtaConfigResolved};
+use crate::summary::{score_statistics, ScoreStatistics};
+use serde::Serialize;
+use std::fmt;
+use tempfile::TempDir;
+
+mod tests;
+
+#[derive(Debug, Clone)]
+pub struct HistoryError {
+    message: String,
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HistoryError! {}", self.message)
+    }
+}
+
+impl From<GitError> for HistoryError {
+    fn from(err: GitError) -> Self {
+        HistoryError {
+            message: err.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FileScore {
+    pub file_name: String,
+    pub fta_score: f64,
+}
+
+/// The scores of the project as of a commit.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct RevisionScores {
+    pub commit: String,
+    /// The commit date, as `YYYY-MM-DD`.
+    pub date: String,
+    pub file_count: usize,
+    pub line_count: usize,
+    /// Statistics of the FTA scores, if any files were analyzed.
+    pub scores: Option<ScoreStatistics>,
+    pub files: Vec<FileScore>,
+}
+
+fn revision_scores(revision: Revision, files: &[FileData]) -> RevisionScores {
+    RevisionScores {
+        commit: revision.commit,
+        date: revision.date,
+        file_count: files.len(),
+        line_count: files.iter().map(|file_data| file_data.line_count).sum(),
+        scores: score_statistics(files),
+        files: files
+            .iter()
+            .map(|file_data| FileScore {
+                file_name: file_data.file_name.clone(),
+                fta_score: file_data.fta_score,
+            })
+            .collect(),
+    }
+}
+
+/// Analyzes every `step`th commit since `since`, oldest first, calling `on_revision` before each
+/// one is analyzed. Commits are checked out in a temporary worktree, so the working tree is left
+/// alone, and are all analyzed with the given config to keep their scores comparable. With
+/// `use_cache`, the results of unchanged files are reused from earlier revisions.
+pub fn history<F>(
+    repo_path: &str,
+    config: &FtaConfigResolved,
+    since: &str,
+    step: usize,
+    use_cache: bool,
+    mut on_revision: F,
+) -> Result<Vec<RevisionScores>, HistoryError>
+where
+    F: FnMut(&Revision, usize, usize),
+{
+    let revisions = revisions(repo_path, since, step)?;
+    if revisions.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let temp_dir = TempDir::new().map_err(|err| HistoryError {
+        message: format!("Unable to create a temporary directory: {}", err),
+    })?;
+    let worktree = Worktree::add(repo_path, &temp_dir.path().join("worktree"))?;
+
+    // Most files don't change between revisions, so they share a cache of their own
+    let mut config = config.clone();
+    config.cache_dir = use_cache.then(|| temp_dir.path().join("cache").display().to_string());
+
+    let mut series = Vec::new();
+    for (index, revision) in revisions.iter().enumerate() {
+        on_revision(revision, index, revisions.len());
+        worktree.checkout(&revision.commit)?;
+
+        let project_path = worktree.project_path().display().to_string();
+        let files = if worktree.project_path().is_dir() {
+            match crate::analyze(&project_path, &config) {
+                Ok(report) => report.files,
+                Err(FtaError::ScoreCapBreached { report, .. }) => report.files,
+                Err(err) => {
+                    return Err(HistoryError {
+                        message: err.to_string(),
+                    })
+                }
+            }
+        } else {
+            Vec::new()
+        };
+        series.push(revision_scores(revision.clone(), &files));
+    }
+
+    Ok(series)
+}
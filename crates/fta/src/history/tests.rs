@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests {
+    use crate::config::get_default_config;
+    use crate::history::history;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_AUTHOR_NAME", "fta")
+            .env("GIT_AUTHOR_EMAIL", "fta@example.com")
+            .env("GIT_COMMITTER_NAME", "fta")
+            .env("GIT_COMMITTER_EMAIL", "fta@example.com")
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    fn commit(dir: &Path, message: &str) {
+        git(dir, &["add", "-A"]);
+        git(dir, &["commit", "-q", "-m", message]);
+    }
+
+    fn file_names(files: &[crate::history::FileScore]) -> Vec<&str> {
+        files.iter().map(|file| file.file_name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_history() {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        let project = dir.path().join("app");
+        fs::create_dir(&project).unwrap();
+
+        fs::write(project.join("a.ts"), "const a = 1;\n").unwrap();
+        commit(dir.path(), "first");
+        fs::write(
+            project.join("a.ts"),
+            "function a(x: number) {\n  if (x > 1) {\n    return x;\n  }\n  return 1;\n}\n",
+        )
+        .unwrap();
+        fs::write(project.join("b.ts"), "const b = 1;\n").unwrap();
+        commit(dir.path(), "second");
+        fs::remove_file(project.join("b.ts")).unwrap();
+        commit(dir.path(), "third");
+        // Uncommitted changes aren't part of the history
+        fs::write(project.join("c.ts"), "const c = 1;\n").unwrap();
+
+        let mut config = get_default_config();
+        config.exclude_under = 0;
+        let repo_path = project.to_str().unwrap();
+
+        let mut progress = Vec::new();
+        let series = history(
+            repo_path,
+            &config,
+            "1 year ago",
+            1,
+            true,
+            |revision, index, total| progress.push((revision.commit.clone(), index, total)),
+        )
+        .unwrap();
+
+        assert_eq!(series.len(), 3);
+        assert_eq!(progress.len(), 3);
+        assert_eq!(progress[2], (series[2].commit.clone(), 2, 3));
+        assert_eq!(file_names(&series[0].files), vec!["a.ts"]);
+        assert_eq!(file_names(&series[1].files), vec!["a.ts", "b.ts"]);
+        assert_eq!(file_names(&series[2].files), vec!["a.ts"]);
+        assert_eq!(series[1].file_count, 2);
+        assert!(series[1].files[0].fta_score > series[0].files[0].fta_score);
+        assert_eq!(
+            series[2].scores.as_ref().unwrap().max,
+            series[1].files[0].fta_score
+        );
+
+        // Every other commit, counting back from the latest, with the same scores without a cache
+        let every_other =
+            history(repo_path, &config, "1 year ago", 2, false, |_, _, _| {}).unwrap();
+        assert_eq!(
+            every_other
+                .iter()
+                .map(|revision| revision.commit.as_str())
+                .collect::<Vec<_>>(),
+            vec![series[0].commit.as_str(), series[2].commit.as_str()]
+        );
+        assert_eq!(every_other[1], series[2]);
+
+        // The worktree is removed once the history has been analyzed
+        let worktrees = Command::new("git")
+            .args(["worktree", "list"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&worktrees.stdout).lines().count(),
+            1
+        );
+        assert!(project.join("c.ts").exists());
+    }
+
+    #[test]
+    fn test_empty_history() {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        fs::write(dir.path().join("a.ts"), "const a = 1;\n").unwrap();
+        commit(dir.path(), "first");
+        let repo_path = dir.path().to_str().unwrap();
+
+        let series = history(
+            repo_path,
+            &get_default_config(),
+            "2099-12-31",
+            1,
+            true,
+            |_, _, _| {},
+        )
+        .unwrap();
+        assert!(series.is_empty());
+
+        let result = history(
+            "/",
+            &get_default_config(),
+            "1 year ago",
+            1,
+            true,
+            |_, _, _| {},
+        );
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .starts_with("HistoryError! GitError! "));
+    }
+}
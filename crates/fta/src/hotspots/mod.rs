@@ -0,0 +1,81 @@
+use crate::git::FileHistory;
+use crate::structs::FileData;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+mod tests;
+
+/// A file that is both complex and frequently changed.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Hotspot {
+    pub file_name: String,
+    pub fta_score: f64,
+    pub assessment: String,
+    /// The number of commits that changed the file within the window.
+    pub commits: usize,
+    /// The number of distinct authors of those commits.
+    pub authors: usize,
+    /// The date of the most recent of those commits, as `YYYY-MM-DD`.
+    pub last_modified: String,
+    /// How urgent the file is, from 0 to 100.
+    pub hotspot_score: f64,
+}
+
+/// Ranks the files changed within the window of `histories`, most urgent first. The hotspot score
+/// is a weighted geometric mean of the FTA score and the number of commits, each relative to the
+/// highest among the changed files, so a file only ranks highly if it does on both counts.
+/// `complexity_weight` ranges from 0, which ranks files by churn alone, to 1, which ranks them by
+/// complexity alone.
+pub fn find_hotspots(
+    files: &[FileData],
+    histories: &HashMap<PathBuf, FileHistory>,
+    complexity_weight: f64,
+) -> Vec<Hotspot> {
+    let changed: Vec<(&FileData, &FileHistory)> = files
+        .iter()
+        .filter_map(|file_data| {
+            histories
+                .get(&PathBuf::from(&file_data.file_name))
+                .map(|history| (file_data, history))
+        })
+        .collect();
+
+    let max_score = changed
+        .iter()
+        .map(|(file_data, _)| file_data.fta_score)
+        .fold(0.0, f64::max);
+    let max_commits = changed
+        .iter()
+        .map(|(_, history)| history.commits)
+        .max()
+        .unwrap_or_default();
+    let relative = |value: f64, max: f64| if max > 0.0 { value / max } else { 0.0 };
+
+    let mut hotspots: Vec<Hotspot> = changed
+        .into_iter()
+        .map(|(file_data, history)| {
+            let complexity = relative(file_data.fta_score, max_score);
+            let churn = relative(history.commits as f64, max_commits as f64);
+
+            Hotspot {
+                file_name: file_data.file_name.clone(),
+                fta_score: file_data.fta_score,
+                assessment: file_data.assessment.clone(),
+                commits: history.commits,
+                authors: history.authors.len(),
+                last_modified: history.last_modified.clone(),
+                hotspot_score: 100.0
+                    * complexity.powf(complexity_weight)
+                    * churn.powf(1.0 - complexity_weight),
+            }
+        })
+        .collect();
+    hotspots.sort_by(|a, b| {
+        b.hotspot_score
+            .total_cmp(&a.hotspot_score)
+            .then_with(|| a.file_name.cmp(&b.file_name))
+    });
+
+    hotspots
+}
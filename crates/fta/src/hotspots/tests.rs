@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use crate::git::FileHistory;
+    use crate::hotspots::find_hotspots;
+    use crate::structs::{test_file_data, FileData};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn history(commits: usize, authors: &[&str]) -> FileHistory {
+        FileHistory {
+            commits,
+            authors: authors.iter().map(|author| author.to_string()).collect(),
+            last_modified: "2024-05-01".to_string(),
+        }
+    }
+
+    fn get_test_data() -> (Vec<FileData>, HashMap<PathBuf, FileHistory>) {
+        let files = vec![
+            test_file_data("parser.ts", 80.0),
+            test_file_data("cart.ts", 50.0),
+            test_file_data("constants.ts", 10.0),
+            test_file_data("legacy.ts", 90.0),
+        ];
+        let histories = HashMap::from([
+            (PathBuf::from("parser.ts"), history(2, &["a@example.com"])),
+            (
+                PathBuf::from("cart.ts"),
+                history(20, &["a@example.com", "b@example.com"]),
+            ),
+            (
+                PathBuf::from("constants.ts"),
+                history(20, &["a@example.com"]),
+            ),
+            (PathBuf::from("deleted.ts"), history(5, &["a@example.com"])),
+        ]);
+        (files, histories)
+    }
+
+    fn ranking(complexity_weight: f64) -> Vec<(String, f64)> {
+        let (files, histories) = get_test_data();
+        find_hotspots(&files, &histories, complexity_weight)
+            .into_iter()
+            .map(|hotspot| {
+                (
+                    hotspot.file_name,
+                    (hotspot.hotspot_score * 100.0).round() / 100.0,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_find_hotspots() {
+        let (files, histories) = get_test_data();
+        let hotspots = find_hotspots(&files, &histories, 0.5);
+
+        // Files that weren't changed within the window, or weren't analyzed, aren't hotspots
+        assert_eq!(hotspots.len(), 3);
+        assert_eq!(hotspots[0].file_name, "cart.ts");
+        assert_eq!(hotspots[0].commits, 20);
+        assert_eq!(hotspots[0].authors, 2);
+        assert_eq!(hotspots[0].last_modified, "2024-05-01");
+        assert_eq!(
+            ranking(0.5),
+            vec![
+                ("cart.ts".to_string(), 79.06),
+                ("constants.ts".to_string(), 35.36),
+                ("parser.ts".to_string(), 31.62),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hotspot_weighting() {
+        assert_eq!(
+            ranking(0.0),
+            vec![
+                ("cart.ts".to_string(), 100.0),
+                ("constants.ts".to_string(), 100.0),
+                ("parser.ts".to_string(), 10.0),
+            ]
+        );
+        assert_eq!(
+            ranking(1.0),
+            vec![
+                ("parser.ts".to_string(), 100.0),
+                ("cart.ts".to_string(), 62.5),
+                ("constants.ts".to_string(), 12.5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_hotspots() {
+        let (files, _) = get_test_data();
+        assert!(find_hotspots(&files, &HashMap::new(), 0.5).is_empty());
+
+        let histories = HashMap::from([(PathBuf::from("cart.ts"), history(1, &[]))]);
+        let hotspots = find_hotspots(&[test_file_data("cart.ts", 0.0)], &histories, 0.5);
+        assert_eq!(hotspots[0].hotspot_score, 0.0);
+        assert_eq!(hotspots[0].authors, 0);
+    }
+}
@@ -1,33 +1,65 @@
+pub mod baseline;
+pub mod cache;
+mod cognitive;
 pub mod config;
 mod cyclo;
+pub mod diff;
+pub mod error;
+mod functions;
+pub mod git;
+pub mod group;
 mod halstead;
+pub mod history;
+pub mod hotspots;
+#[cfg(feature = "lsp")]
+pub mod lsp;
 pub mod parse;
+mod sfc;
 mod structs;
+pub mod summary;
+mod suppression;
 mod utils;
 mod walk;
 
 #[cfg(feature = "use_output")]
 pub mod output;
+#[cfg(feature = "watch")]
+pub mod watch;
 
+pub use structs::{
+    AnalysisReport, AssessmentBand, AssessmentThresholds, ConfigOverride, FileData,
+    FtaConfigResolved, FunctionData, HalsteadMetrics, Severity, Suppression,
+};
+
+use cache::Cache;
+use config::{assessment_bands, resolve_for_file};
+use error::{find_score_cap_breaches, FtaError, ParseFailure};
 use ignore::DirEntry;
-use ignore::WalkBuilder;
+use ignore::{WalkBuilder, WalkParallel};
 use log::debug;
 use log::warn;
+use parse::{Directive, LineMap};
+use std::collections::HashSet;
 use std::env;
 use std::fs;
-use structs::{FileData, FtaConfigResolved, HalsteadMetrics};
+use std::path::{Path, PathBuf};
+use swc_common::Spanned;
 use swc_ecma_ast::Module;
-use swc_ecma_parser::error::Error;
-use utils::{check_score_cap_breach, get_assessment, is_valid_file, warn_about_language};
+use utils::{get_assessment, is_valid_file, warn_about_language};
 use walk::walk_and_analyze_files;
 
 pub fn analyze_file(module: &Module, line_count: usize) -> (usize, HalsteadMetrics, f64) {
     let cyclo = cyclo::cyclomatic_complexity(module);
     let halstead_metrics = halstead::analyze_module(module);
+    let fta_score = calculate_fta_score(line_count, cyclo, halstead_metrics.vocabulary_size);
+
+    (cyclo, halstead_metrics, fta_score)
+}
 
+fn calculate_fta_score(line_count: usize, cyclo: usize, vocabulary_size: usize) -> f64 {
     let line_count_float = line_count as f64;
     let cyclo_float = cyclo as f64;
-    let vocab_float = halstead_metrics.vocabulary_size as f64;
+    let vocab_float = vocabulary_size as f64;
 
     const MINIMUM_CYCLO: f64 = 1.0;
 
@@ -47,118 +79,259 @@ pub fn analyze_file(module: &Module, line_count: usize) -> (usize, HalsteadMetri
         fta_score = 0.0;
     }
 
-    (cyclo, halstead_metrics, fta_score)
+    fta_score
 }
 
-fn analyze_parsed_code(file_name: String, module: Module, line_count: usize) -> FileData {
+fn analyze_parsed_code(
+    file_name: String,
+    module: Module,
+    line_count: usize,
+    line_map: &LineMap,
+    directives: &[Directive],
+    assessment_bands: &[AssessmentBand],
+) -> FileData {
     let (cyclo, halstead, fta_score) = analyze_file(&module, line_count);
-    debug!("{} cyclo: {}, halstead: {:?}", file_name, cyclo, halstead);
+    let cognitive = cognitive::cognitive_complexity(&module);
+    debug!(
+        "{} cyclo: {}, cognitive: {}, halstead: {:?}",
+        file_name, cyclo, cognitive, halstead
+    );
+    let functions = functions::analyze_functions(&module, line_map);
+    let (assessment, severity) = get_assessment(fta_score, assessment_bands);
 
-    FileData {
+    let mut file_data = FileData {
         file_name,
         cyclo,
+        cognitive,
         halstead,
         fta_score,
         line_count,
-        assessment: get_assessment(fta_score),
-    }
-}
-
-fn collect_results(
-    entry: &DirEntry,
-    repo_path: &str,
-    module: Module,
-    line_count: usize,
-    score_cap: usize,
-) -> FileData {
-    // Parse the source code and run the analysis
-    let file_name = entry
-        .path()
-        .strip_prefix(repo_path)
-        .unwrap()
-        .display()
-        .to_string();
-    let file_name_cloned = file_name.clone();
-    let file_data = analyze_parsed_code(file_name, module, line_count);
-
-    // Keep a record of the fta_score before moving the FileData
-    let fta_score = file_data.fta_score;
-
-    // Check if the score cap is breached
-    check_score_cap_breach(file_name_cloned.clone(), fta_score, score_cap);
+        assessment,
+        severity,
+        functions,
+        suppression: None,
+    };
+    suppression::apply_directives(&mut file_data, directives, line_map);
 
     file_data
 }
 
 fn do_analysis(
-    entry: &DirEntry,
-    repo_path: &str,
+    file_name: &str,
     config: &FtaConfigResolved,
     source_code: &str,
     use_tsx: bool,
-) -> Result<FileData, Error> {
-    let (result, line_count) = parse::parse_module(source_code, use_tsx, config.include_comments);
+) -> Result<FileData, ParseFailure> {
+    let (result, line_count, directives) =
+        parse::parse_module_with_directives(source_code, use_tsx, config.include_comments);
+    let line_map = LineMap::new(source_code);
 
     match result {
-        Ok(module) => Ok(collect_results(
-            entry,
-            repo_path,
+        Ok(module) => Ok(analyze_parsed_code(
+            file_name.to_string(),
             module,
             line_count,
-            config.score_cap,
+            &line_map,
+            &directives,
+            &assessment_bands(config),
         )),
-        Err(err) => Err(err),
+        Err(err) => Err(ParseFailure {
+            file_name: file_name.to_string(),
+            message: err.kind().msg().to_string(),
+            line: line_map.original_line(err.span().lo),
+            column: line_map.column(err.span().lo),
+        }),
     }
 }
 
+/// Analyzes the source code of a single file, without reading it from disk. `file_name` is
+/// relative to the project, and is used to pick the language and apply any overrides. Files are
+/// analyzed regardless of the extension and exclusion rules.
+pub fn analyze_source(
+    file_name: &str,
+    source_code: &str,
+    config: &FtaConfigResolved,
+) -> Result<FileData, ParseFailure> {
+    let config = resolve_for_file(config, file_name);
+    analyze_resolved_source(file_name, source_code, &config)
+}
+
+fn analyze_resolved_source(
+    file_name: &str,
+    source_code: &str,
+    config: &FtaConfigResolved,
+) -> Result<FileData, ParseFailure> {
+    let file_extension = Path::new(file_name)
+        .extension()
+        .and_then(std::ffi::OsStr::to_str)
+        .unwrap_or_default();
+
+    // Single-file components are analyzed by their scripts alone
+    let (source_code, use_tsx) = if sfc::is_component(file_extension) {
+        let script = sfc::extract_scripts(source_code, file_extension);
+        (script.source, script.use_tsx)
+    } else {
+        let use_tsx = file_extension == "tsx" || file_extension == "jsx";
+        (source_code.to_string(), use_tsx)
+    };
+
+    do_analysis(file_name, config, &source_code, use_tsx).or_else(|failure| {
+        warn_about_language(file_name, use_tsx);
+        // If neither works, the error for the language matching the extension is the relevant one
+        do_analysis(file_name, config, &source_code, !use_tsx).map_err(|_| failure)
+    })
+}
+
 fn process_entry(
     entry: DirEntry,
     repo_path: &String,
     config: &FtaConfigResolved,
-) -> Option<Vec<FileData>> {
-    let file_name = entry.path().display();
-    let source_code = match fs::read_to_string(file_name.to_string()) {
+    cache: Option<&Cache>,
+) -> Result<Vec<FileData>, ParseFailure> {
+    let source_code = match fs::read_to_string(entry.path()) {
         Ok(code) => code,
-        Err(_) => return None,
+        Err(_) => return Ok(Vec::new()),
     };
 
-    let file_extension = entry
+    // Apply any overrides matching this file
+    let relative_path = entry
         .path()
-        .extension()
-        .and_then(std::ffi::OsStr::to_str)
-        .unwrap_or_default()
+        .strip_prefix(repo_path)
+        .unwrap()
+        .display()
         .to_string();
-    let use_tsx = file_extension == "tsx" || file_extension == "jsx";
+    let config = resolve_for_file(config, &relative_path);
 
-    let mut file_data_result = do_analysis(&entry, repo_path, &config, &source_code, use_tsx);
+    // Reuse the previous result if neither the file nor the relevant config changed
+    let cache_key = cache.map(|_| cache::key(&source_code, &config));
+    if let (Some(cache), Some(cache_key)) = (cache, &cache_key) {
+        if let Some(data) = cache.get(&relative_path, cache_key) {
+            debug!("{} is unchanged since the last run", relative_path);
+            return Ok(filter_small_files(data, &config));
+        }
+    }
 
-    if file_data_result.is_err() {
-        warn_about_language(&file_name.to_string(), use_tsx);
-        file_data_result = do_analysis(&entry, repo_path, &config, &source_code, !use_tsx);
+    match analyze_resolved_source(&relative_path, &source_code, &config) {
+        Ok(data) => {
+            if let (Some(cache), Some(cache_key)) = (cache, cache_key) {
+                cache.insert(&relative_path, cache_key, &data);
+            }
+            Ok(filter_small_files(data, &config))
+        }
+        Err(err) => {
+            warn!("{}", err);
+            Err(err)
+        }
     }
+}
 
-    if file_data_result.is_err() {
-        warn!(
-            "Failed to analyze {}: {:?}",
-            file_name,
-            file_data_result.unwrap_err()
-        );
-        return None;
+/// Only include files that are equal to or greater than the `exclude_under` option
+fn filter_small_files(data: FileData, config: &FtaConfigResolved) -> Vec<FileData> {
+    if data.line_count > config.exclude_under {
+        vec![data]
+    } else {
+        Vec::new()
     }
+}
+
+/// Like `analyze_source`, but reports the file the same way as `analyze`, including files that
+/// can't be parsed and score cap breaches.
+pub fn analyze_buffer(
+    file_name: &str,
+    source_code: &str,
+    config: &FtaConfigResolved,
+) -> Result<AnalysisReport, FtaError> {
+    init_logger();
 
-    let mut file_data_list: Vec<FileData> = Vec::new();
+    let report = match analyze_source(file_name, source_code, config) {
+        Ok(file_data) => AnalysisReport {
+            files: vec![file_data],
+            failures: Vec::new(),
+        },
+        Err(failure) => {
+            warn!("{}", failure);
+            AnalysisReport {
+                files: Vec::new(),
+                failures: vec![failure],
+            }
+        }
+    };
+
+    check_score_caps(report, config)
+}
+
+/// Analyzes every file in `repo_path`. If any file is beyond the score cap, the full report is
+/// returned as part of `FtaError::ScoreCapBreached`.
+pub fn analyze(repo_path: &String, config: &FtaConfigResolved) -> Result<AnalysisReport, FtaError> {
+    analyze_walk(repo_path, config, None)
+}
 
-    // Only include files that are equal to or greater than the `exclude_under` option
-    match file_data_result {
-        Ok(data) if data.line_count > config.exclude_under => file_data_list.push(data),
-        _ => {}
+/// Like `analyze`, but only analyzes the given files. Paths are relative to `repo_path`, and files
+/// are still subject to the usual extension and exclusion rules. Only the directories leading to
+/// the files are walked, so this stays fast however large the project is.
+pub fn analyze_paths(
+    repo_path: &String,
+    config: &FtaConfigResolved,
+    paths: &HashSet<PathBuf>,
+) -> Result<AnalysisReport, FtaError> {
+    analyze_walk(repo_path, config, Some(paths))
+}
+
+/// Analyzes the given files without walking the rest of `repo_path`. Paths are relative to
+/// `repo_path` or absolute, and files are subject to the usual extension and exclusion rules, but
+/// not to `.gitignore`. Files that don't exist are skipped with a warning.
+pub fn analyze_files(
+    repo_path: &String,
+    config: &FtaConfigResolved,
+    files: &HashSet<PathBuf>,
+) -> Result<AnalysisReport, FtaError> {
+    init_logger();
+
+    let repo_root = fs::canonicalize(repo_path).unwrap_or_else(|_| PathBuf::from(repo_path));
+    let mut roots = Vec::new();
+    for file in files {
+        // Every file is walked from within the project, so that its name is relative to it
+        let relative_path = match file
+            .strip_prefix(repo_path)
+            .or_else(|_| file.strip_prefix(&repo_root))
+        {
+            Ok(relative_path) => relative_path,
+            Err(_) if file.is_absolute() => {
+                warn!(
+                    "{} is outside of {}, skipping it",
+                    file.display(),
+                    repo_path
+                );
+                continue;
+            }
+            Err(_) => file.as_path(),
+        };
+        let path = Path::new(repo_path).join(relative_path);
+        if path.is_file() {
+            roots.push(path);
+        } else {
+            warn!("{} doesn't exist, skipping it", file.display());
+        }
+    }
+    roots.sort();
+
+    let Some((first, rest)) = roots.split_first() else {
+        return Ok(AnalysisReport {
+            files: Vec::new(),
+            failures: Vec::new(),
+        });
+    };
+    let mut builder = WalkBuilder::new(first);
+    for root in rest {
+        builder.add(root);
     }
+    let walk = builder.threads(config.threads).build_parallel();
 
-    Some(file_data_list)
+    // Files outside of the list weren't looked at, so they're kept in the cache
+    analyze_entries(walk, repo_path, config, is_valid_file, false)
 }
 
-pub fn analyze(repo_path: &String, config: &FtaConfigResolved) -> Vec<FileData> {
-    // Initialize the logger
+fn init_logger() {
     let mut builder = env_logger::Builder::new();
 
     // Check if debug mode is enabled using an environment variable
@@ -167,13 +340,98 @@ pub fn analyze(repo_path: &String, config: &FtaConfigResolved) -> Vec<FileData>
     } else {
         builder.filter_level(log::LevelFilter::Info);
     }
-    builder.init();
+    // Ignore the error if a logger was already initialized, e.g. when analyzing more than once
+    let _ = builder.try_init();
+}
 
-    let walk = WalkBuilder::new(repo_path)
+fn analyze_walk(
+    repo_path: &String,
+    config: &FtaConfigResolved,
+    paths: Option<&HashSet<PathBuf>>,
+) -> Result<AnalysisReport, FtaError> {
+    init_logger();
+
+    let mut builder = WalkBuilder::new(repo_path);
+    builder
         .git_ignore(true)
         .git_exclude(true)
         .standard_filters(true)
-        .build();
+        .threads(config.threads);
+    if let Some(paths) = paths {
+        // Skip directories that none of the paths are in, while still applying their ignore files
+        let repo_path = repo_path.clone();
+        let paths = paths.clone();
+        builder.filter_entry(move |entry| {
+            !entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_dir())
+                || entry
+                    .path()
+                    .strip_prefix(&repo_path)
+                    .is_ok_and(|dir| paths.iter().any(|path| path.starts_with(dir)))
+        });
+    }
+    let walk = builder.build_parallel();
+
+    let is_selected = |repo_path: &String, entry: &DirEntry, config: &FtaConfigResolved| {
+        let selected = match paths {
+            Some(paths) => entry
+                .path()
+                .strip_prefix(repo_path)
+                .is_ok_and(|relative_path| paths.contains(relative_path)),
+            None => true,
+        };
+        selected && is_valid_file(repo_path, entry, config)
+    };
+
+    // Files outside of the given paths weren't looked at, so they're kept in the cache
+    analyze_entries(walk, repo_path, config, is_selected, paths.is_none())
+}
+
+fn analyze_entries<V>(
+    walk: WalkParallel,
+    repo_path: &String,
+    config: &FtaConfigResolved,
+    is_selected: V,
+    prune_cache: bool,
+) -> Result<AnalysisReport, FtaError>
+where
+    V: Fn(&String, &DirEntry, &FtaConfigResolved) -> bool + Sync,
+{
+    let cache = config
+        .cache_dir
+        .as_ref()
+        .map(|cache_dir| Cache::load(Path::new(cache_dir)));
+
+    let (files, failures) = walk_and_analyze_files(
+        walk,
+        repo_path,
+        config,
+        |entry, repo_path, config| process_entry(entry, repo_path, config, cache.as_ref()),
+        is_selected,
+    );
+    let report = AnalysisReport { files, failures };
+
+    if let Some(cache) = cache {
+        cache.save(prune_cache);
+    }
+
+    check_score_caps(report, config)
+}
+
+/// Fails with the full report if any of its files are beyond their score cap.
+fn check_score_caps(
+    report: AnalysisReport,
+    config: &FtaConfigResolved,
+) -> Result<AnalysisReport, FtaError> {
+    let breaches = find_score_cap_breaches(&report, config);
+    if !breaches.is_empty() {
+        return Err(FtaError::ScoreCapBreached {
+            score_cap: config.score_cap,
+            breaches,
+            report: Box::new(report),
+        });
+    }
 
-    walk_and_analyze_files(walk, repo_path, config, process_entry, is_valid_file)
+    Ok(report)
 }
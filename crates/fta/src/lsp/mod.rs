@@ -0,0 +1,552 @@
+use crate::analyze_source;
+use crate::config::{assessment_bands, get_default_config, read_config, resolve_for_file};
+use crate::structs::{FileData, FtaConfigResolved, FunctionData, HalsteadMetrics, Severity};
+use crate::utils::{get_assessment, is_valid_path};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+    Notification as _, PublishDiagnostics, ShowMessage,
+};
+use lsp_types::request::{CodeLensRequest, HoverRequest, Request as _};
+use lsp_types::{
+    CodeLens, CodeLensOptions, CodeLensParams, Command, Diagnostic, DiagnosticSeverity,
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DidSaveTextDocumentParams, Hover, HoverContents, HoverParams, HoverProviderCapability,
+    InitializeParams, InitializeResult, MarkupContent, MarkupKind, MessageType, NumberOrString,
+    Position, PublishDiagnosticsParams, Range, ServerCapabilities, ServerInfo, ShowMessageParams,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TextDocumentSyncSaveOptions, Url,
+};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+mod tests;
+
+const SOURCE: &str = "fta";
+const SCORE_CAP_CODE: &str = "fta/score-cap";
+const ASSESSMENT_CODE: &str = "fta/assessment";
+const FUNCTION_ASSESSMENT_CODE: &str = "fta/function-assessment";
+
+#[derive(Debug, Clone)]
+pub struct LspError {
+    message: String,
+}
+
+impl fmt::Display for LspError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LspError! {}", self.message)
+    }
+}
+
+impl LspError {
+    fn new(err: impl fmt::Display) -> LspError {
+        LspError {
+            message: err.to_string(),
+        }
+    }
+}
+
+/// An open document, along with the last successful analysis of it. While a document can't be
+/// parsed, e.g. halfway through an edit, the previous analysis is kept.
+struct Document {
+    text: String,
+    file_data: Option<FileData>,
+}
+
+struct Server {
+    connection: Connection,
+    root: Option<PathBuf>,
+    config: FtaConfigResolved,
+    documents: HashMap<Url, Document>,
+}
+
+fn diagnostic_severity(severity: Severity) -> Option<DiagnosticSeverity> {
+    match severity {
+        Severity::Error => Some(DiagnosticSeverity::ERROR),
+        Severity::Warning => Some(DiagnosticSeverity::WARNING),
+        Severity::Note => Some(DiagnosticSeverity::INFORMATION),
+        Severity::None => None,
+    }
+}
+
+/// The range of a whole line, given its one-based line number. Positions are in UTF-16 code
+/// units, as required by the protocol.
+fn line_range(text: &str, line: usize) -> Range {
+    let line = line.saturating_sub(1);
+    let length = text
+        .lines()
+        .nth(line)
+        .map(|content| content.encode_utf16().count())
+        .unwrap_or_default();
+
+    Range::new(
+        Position::new(line as u32, 0),
+        Position::new(line as u32, length as u32),
+    )
+}
+
+fn diagnostic(
+    range: Range,
+    severity: DiagnosticSeverity,
+    code: &str,
+    message: String,
+) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(severity),
+        code: Some(NumberOrString::String(code.to_string())),
+        source: Some(SOURCE.to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+/// Diagnostics for a file beyond the score cap, and for the file and its functions when their
+/// assessment has a severity. File diagnostics are reported on the first line. Files and
+/// functions that are ignored with an inline comment don't get any diagnostics.
+pub fn diagnostics(
+    text: &str,
+    file_data: &FileData,
+    config: &FtaConfigResolved,
+) -> Vec<Diagnostic> {
+    let config = resolve_for_file(config, &file_data.file_name);
+    let mut diagnostics = Vec::new();
+    if file_data.is_ignored() {
+        return diagnostics;
+    }
+
+    let score_cap = file_data.score_cap(config.score_cap).unwrap_or(usize::MAX);
+    if file_data.fta_score > score_cap as f64 {
+        diagnostics.push(diagnostic(
+            line_range(text, 1),
+            DiagnosticSeverity::ERROR,
+            SCORE_CAP_CODE,
+            format!(
+                "File has an FTA score of {:.2}, which is beyond the score cap of {}.",
+                file_data.fta_score, score_cap
+            ),
+        ));
+    }
+    if let Some(severity) = diagnostic_severity(file_data.severity) {
+        diagnostics.push(diagnostic(
+            line_range(text, 1),
+            severity,
+            ASSESSMENT_CODE,
+            format!(
+                "File has an FTA score of {:.2} ({}).",
+                file_data.fta_score, file_data.assessment
+            ),
+        ));
+    }
+
+    let bands = assessment_bands(&config);
+    for function in &file_data.functions {
+        if function.suppression.is_some() {
+            continue;
+        }
+        let (assessment, severity) = get_assessment(function.fta_score, &bands);
+        if let Some(severity) = diagnostic_severity(severity) {
+            diagnostics.push(diagnostic(
+                line_range(text, function.start_line),
+                severity,
+                FUNCTION_ASSESSMENT_CODE,
+                format!(
+                    "Function `{}` has an FTA score of {:.2} ({}).",
+                    function.name, function.fta_score, assessment
+                ),
+            ));
+        }
+    }
+
+    diagnostics
+}
+
+fn code_lens(range: Range, title: String) -> CodeLens {
+    CodeLens {
+        range,
+        command: Some(Command {
+            title,
+            command: String::new(),
+            arguments: None,
+        }),
+        data: None,
+    }
+}
+
+/// A code lens with the scores of the file on its first line, and of each function above it.
+pub fn code_lenses(text: &str, file_data: &FileData) -> Vec<CodeLens> {
+    let mut code_lenses = vec![code_lens(
+        line_range(text, 1),
+        format!(
+            "FTA score {:.2} ({}) · cyclo {} · cognitive {} · {} lines",
+            file_data.fta_score,
+            file_data.assessment,
+            file_data.cyclo,
+            file_data.cognitive,
+            file_data.line_count
+        ),
+    )];
+
+    for function in &file_data.functions {
+        code_lenses.push(code_lens(
+            line_range(text, function.start_line),
+            format!(
+                "FTA score {:.2} · cyclo {} · cognitive {}",
+                function.fta_score, function.cyclo, function.cognitive
+            ),
+        ));
+    }
+
+    code_lenses
+}
+
+fn metrics_table(
+    cyclo: usize,
+    cognitive: usize,
+    line_count: usize,
+    halstead: &HalsteadMetrics,
+) -> String {
+    format!(
+        "| Metric | Value |\n|---|---|\n| Cyclomatic complexity | {} |\n| Cognitive complexity | {} |\n| Lines | {} |\n| Halstead vocabulary | {} |\n| Halstead volume | {:.2} |\n| Halstead difficulty | {:.2} |\n| Halstead effort | {:.2} |\n| Halstead bugs | {:.2} |",
+        cyclo,
+        cognitive,
+        line_count,
+        halstead.vocabulary_size,
+        halstead.volume,
+        halstead.difficulty,
+        halstead.effort,
+        halstead.bugs
+    )
+}
+
+/// The metrics of the innermost function starting on `line` (zero-based), or of the file on its
+/// first line.
+pub fn hover(
+    text: &str,
+    file_data: &FileData,
+    line: u32,
+    config: &FtaConfigResolved,
+) -> Option<Hover> {
+    let line = line as usize + 1;
+    let function = file_data
+        .functions
+        .iter()
+        .filter(|function| function.start_line == line)
+        .min_by_key(|function| function.end_line);
+
+    let value = match function {
+        Some(FunctionData {
+            name,
+            cyclo,
+            cognitive,
+            halstead,
+            line_count,
+            fta_score,
+            ..
+        }) => {
+            let bands = assessment_bands(&resolve_for_file(config, &file_data.file_name));
+            format!(
+                "**{}**: FTA score {:.2} ({})\n\n{}",
+                name,
+                fta_score,
+                get_assessment(*fta_score, &bands).0,
+                metrics_table(*cyclo, *cognitive, *line_count, halstead)
+            )
+        }
+        None if line == 1 => format!(
+            "**{}**: FTA score {:.2} ({})\n\n{}",
+            file_data.file_name,
+            file_data.fta_score,
+            file_data.assessment,
+            metrics_table(
+                file_data.cyclo,
+                file_data.cognitive,
+                file_data.line_count,
+                &file_data.halstead
+            )
+        ),
+        None => return None,
+    };
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value,
+        }),
+        range: Some(line_range(text, line)),
+    })
+}
+
+/// The workspace root, preferring the first workspace folder.
+#[allow(deprecated)]
+fn workspace_root(params: &InitializeParams) -> Option<PathBuf> {
+    params
+        .workspace_folders
+        .as_ref()
+        .and_then(|folders| folders.first())
+        .map(|folder| &folder.uri)
+        .or(params.root_uri.as_ref())
+        .and_then(|uri| uri.to_file_path().ok())
+}
+
+fn server_capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(
+            TextDocumentSyncOptions {
+                open_close: Some(true),
+                change: Some(TextDocumentSyncKind::FULL),
+                save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+                ..Default::default()
+            },
+        )),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        code_lens_provider: Some(CodeLensOptions {
+            resolve_provider: Some(false),
+        }),
+        ..Default::default()
+    }
+}
+
+fn parse_params<P: DeserializeOwned>(params: serde_json::Value) -> Result<P, LspError> {
+    serde_json::from_value(params).map_err(LspError::new)
+}
+
+impl Server {
+    fn send(&self, message: Message) -> Result<(), LspError> {
+        self.connection.sender.send(message).map_err(LspError::new)
+    }
+
+    fn notify(&self, method: &str, params: impl serde::Serialize) -> Result<(), LspError> {
+        self.send(Message::Notification(Notification::new(
+            method.to_string(),
+            params,
+        )))
+    }
+
+    fn config_path(&self) -> Option<PathBuf> {
+        self.root.as_ref().map(|root| root.join("fta.json"))
+    }
+
+    /// Reads `fta.json` from the workspace root. An invalid config is reported to the user, and
+    /// the defaults are used instead.
+    fn load_config(&mut self) -> Result<(), LspError> {
+        let config = match self.config_path() {
+            Some(config_path) => read_config(config_path.display().to_string(), false),
+            None => Ok(get_default_config()),
+        };
+
+        match config {
+            Ok(config) => self.config = config,
+            Err(err) => {
+                self.config = get_default_config();
+                self.notify(
+                    ShowMessage::METHOD,
+                    ShowMessageParams {
+                        typ: MessageType::ERROR,
+                        message: err.to_string(),
+                    },
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The path of a document relative to the workspace root, if it's a file that FTA analyzes.
+    fn file_name(&self, uri: &Url) -> Option<String> {
+        let path = uri.to_file_path().ok()?;
+        let relative_path = match &self.root {
+            Some(root) => path.strip_prefix(root).unwrap_or(&path),
+            None => Path::new(path.file_name()?),
+        }
+        .display()
+        .to_string();
+
+        is_valid_path(&relative_path, &self.config).then_some(relative_path)
+    }
+
+    /// Analyzes a document and publishes its diagnostics. Documents that can't be parsed keep
+    /// their previous diagnostics.
+    fn analyze(&mut self, uri: &Url) -> Result<(), LspError> {
+        let Some(file_name) = self.file_name(uri) else {
+            return Ok(());
+        };
+        let Some(document) = self.documents.get_mut(uri) else {
+            return Ok(());
+        };
+
+        // Files under `exclude_under` are left out, as they are in reports
+        let exclude_under = resolve_for_file(&self.config, &file_name).exclude_under;
+        match analyze_source(&file_name, &document.text, &self.config) {
+            Ok(file_data) if file_data.line_count > exclude_under => {
+                document.file_data = Some(file_data)
+            }
+            Ok(_) => document.file_data = None,
+            Err(_) => return Ok(()),
+        }
+
+        let diagnostics = match &document.file_data {
+            Some(file_data) => diagnostics(&document.text, file_data, &self.config),
+            None => Vec::new(),
+        };
+        self.notify(
+            PublishDiagnostics::METHOD,
+            PublishDiagnosticsParams {
+                uri: uri.clone(),
+                diagnostics,
+                version: None,
+            },
+        )
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> Result<(), LspError> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams = parse_params(notification.params)?;
+                let uri = params.text_document.uri;
+                self.documents.insert(
+                    uri.clone(),
+                    Document {
+                        text: params.text_document.text,
+                        file_data: None,
+                    },
+                );
+                self.analyze(&uri)
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams = parse_params(notification.params)?;
+                let uri = params.text_document.uri;
+                // Documents are synced in full, so the last change holds the whole text
+                if let (Some(document), Some(change)) = (
+                    self.documents.get_mut(&uri),
+                    params.content_changes.into_iter().last(),
+                ) {
+                    document.text = change.text;
+                }
+                self.analyze(&uri)
+            }
+            DidSaveTextDocument::METHOD => {
+                let params: DidSaveTextDocumentParams = parse_params(notification.params)?;
+                let saved_path = params.text_document.uri.to_file_path().ok();
+                if saved_path.is_none() || saved_path != self.config_path() {
+                    return Ok(());
+                }
+
+                // Saving the config affects every open document
+                self.load_config()?;
+                let uris: Vec<Url> = self.documents.keys().cloned().collect();
+                for uri in uris {
+                    self.analyze(&uri)?;
+                }
+                Ok(())
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams = parse_params(notification.params)?;
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+                self.notify(
+                    PublishDiagnostics::METHOD,
+                    PublishDiagnosticsParams {
+                        uri,
+                        diagnostics: Vec::new(),
+                        version: None,
+                    },
+                )
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn handle_request(&self, request: Request) -> Result<(), LspError> {
+        let response = match request.method.as_str() {
+            HoverRequest::METHOD => {
+                let params: HoverParams = parse_params(request.params)?;
+                let position = params.text_document_position_params;
+                let hover = self
+                    .documents
+                    .get(&position.text_document.uri)
+                    .and_then(|document| {
+                        let file_data = document.file_data.as_ref()?;
+                        hover(
+                            &document.text,
+                            file_data,
+                            position.position.line,
+                            &self.config,
+                        )
+                    });
+                Response::new_ok(request.id, hover)
+            }
+            CodeLensRequest::METHOD => {
+                let params: CodeLensParams = parse_params(request.params)?;
+                let code_lenses =
+                    self.documents
+                        .get(&params.text_document.uri)
+                        .and_then(|document| {
+                            let file_data = document.file_data.as_ref()?;
+                            Some(code_lenses(&document.text, file_data))
+                        });
+                Response::new_ok(request.id, code_lenses)
+            }
+            _ => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unsupported request: {}", request.method),
+            ),
+        };
+
+        self.send(Message::Response(response))
+    }
+}
+
+/// Runs the language server on `connection` until the client shuts it down.
+fn serve(connection: Connection) -> Result<(), LspError> {
+    let (id, params) = connection.initialize_start().map_err(LspError::new)?;
+    let params: InitializeParams = parse_params(params)?;
+    let result = InitializeResult {
+        capabilities: server_capabilities(),
+        server_info: Some(ServerInfo {
+            name: SOURCE.to_string(),
+            version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        }),
+    };
+    connection
+        .initialize_finish(id, serde_json::to_value(result).unwrap())
+        .map_err(LspError::new)?;
+
+    let mut server = Server {
+        connection,
+        root: workspace_root(&params),
+        config: get_default_config(),
+        documents: HashMap::new(),
+    };
+    server.load_config()?;
+
+    while let Ok(message) = server.connection.receiver.recv() {
+        match message {
+            Message::Request(request) => {
+                if server
+                    .connection
+                    .handle_shutdown(&request)
+                    .map_err(LspError::new)?
+                {
+                    return Ok(());
+                }
+                server.handle_request(request)?;
+            }
+            Message::Notification(notification) => server.handle_notification(notification)?,
+            Message::Response(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs a language server over stdio, which publishes diagnostics for complex files and
+/// functions, and shows their metrics in code lenses and hovers.
+pub fn run() -> Result<(), LspError> {
+    let (connection, io_threads) = Connection::stdio();
+    serve(connection)?;
+    io_threads.join().map_err(LspError::new)
+}
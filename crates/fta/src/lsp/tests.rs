@@ -0,0 +1,305 @@
+#[cfg(test)]
+mod tests {
+    use crate::config::get_default_config;
+    use crate::lsp::{code_lenses, diagnostics, hover, line_range, serve};
+    use crate::structs::{
+        test_file_data, test_halstead, FileData, FunctionData, Severity, Suppression,
+    };
+    use lsp_server::{Connection, Message, Notification, Request, RequestId};
+    use lsp_types::{DiagnosticSeverity, HoverContents, NumberOrString, Position, Range, Url};
+    use serde_json::{json, Value};
+    use std::fs;
+    use std::thread;
+    use tempfile::TempDir;
+
+    fn function_data(
+        name: &str,
+        start_line: usize,
+        end_line: usize,
+        fta_score: f64,
+    ) -> FunctionData {
+        FunctionData {
+            name: name.to_string(),
+            start_line,
+            end_line,
+            cyclo: 2,
+            cognitive: 1,
+            halstead: test_halstead(),
+            line_count: end_line - start_line + 1,
+            fta_score,
+            suppression: None,
+        }
+    }
+
+    fn file_data(fta_score: f64, assessment: &str, severity: Severity) -> FileData {
+        FileData {
+            cyclo: 3,
+            cognitive: 2,
+            line_count: 8,
+            assessment: assessment.to_string(),
+            severity,
+            functions: vec![
+                function_data("total", 2, 4, 20.0),
+                function_data("checkout", 5, 8, 65.0),
+            ],
+            ..test_file_data("src/cart.ts", fta_score)
+        }
+    }
+
+    const TEXT: &str = "import { tax } from './tax';\nfunction total() {\n  return 1;\n}\nfunction checkout() {\n  const ü = total();\n  return ü;\n}\n";
+
+    #[test]
+    fn test_line_range() {
+        assert_eq!(
+            line_range(TEXT, 6),
+            Range::new(Position::new(5, 0), Position::new(5, 20))
+        );
+        assert_eq!(
+            line_range(TEXT, 100),
+            Range::new(Position::new(99, 0), Position::new(99, 0))
+        );
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let mut config = get_default_config();
+        config.score_cap = 50;
+        let diagnostics = diagnostics(
+            TEXT,
+            &file_data(55.0, "Could be better", Severity::Warning),
+            &config,
+        );
+
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| (
+                    diagnostic.range.start.line,
+                    diagnostic.severity.unwrap(),
+                    diagnostic.code.clone().unwrap(),
+                    diagnostic.message.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    0,
+                    DiagnosticSeverity::ERROR,
+                    NumberOrString::String("fta/score-cap".to_string()),
+                    "File has an FTA score of 55.00, which is beyond the score cap of 50."
+                ),
+                (
+                    0,
+                    DiagnosticSeverity::WARNING,
+                    NumberOrString::String("fta/assessment".to_string()),
+                    "File has an FTA score of 55.00 (Could be better)."
+                ),
+                (
+                    4,
+                    DiagnosticSeverity::ERROR,
+                    NumberOrString::String("fta/function-assessment".to_string()),
+                    "Function `checkout` has an FTA score of 65.00 (Needs improvement)."
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_diagnostics_for_simple_files() {
+        let mut file_data = file_data(20.0, "OK", Severity::None);
+        file_data.functions.pop();
+
+        assert!(diagnostics(TEXT, &file_data, &get_default_config()).is_empty());
+    }
+
+    #[test]
+    fn test_suppressed_diagnostics() {
+        let mut config = get_default_config();
+        config.score_cap = 50;
+        let mut file_data = file_data(55.0, "Could be better", Severity::Warning);
+        file_data.functions[1].suppression = Some(Suppression::IgnoreFunction {
+            line: 5,
+            reason: None,
+        });
+        file_data.suppression = Some(Suppression::ScoreCap {
+            line: 1,
+            score_cap: 60,
+            reason: None,
+        });
+
+        assert_eq!(
+            diagnostics(TEXT, &file_data, &config)
+                .iter()
+                .map(|diagnostic| diagnostic.code.clone().unwrap())
+                .collect::<Vec<_>>(),
+            vec![NumberOrString::String("fta/assessment".to_string())]
+        );
+
+        file_data.suppression = Some(Suppression::IgnoreFile {
+            line: 1,
+            reason: Some("generated".to_string()),
+        });
+        assert!(diagnostics(TEXT, &file_data, &config).is_empty());
+    }
+
+    #[test]
+    fn test_code_lenses() {
+        let code_lenses = code_lenses(TEXT, &file_data(20.0, "OK", Severity::None));
+
+        assert_eq!(
+            code_lenses
+                .iter()
+                .map(|code_lens| (
+                    code_lens.range.start.line,
+                    code_lens.command.as_ref().unwrap().title.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, "FTA score 20.00 (OK) · cyclo 3 · cognitive 2 · 8 lines"),
+                (1, "FTA score 20.00 · cyclo 2 · cognitive 1"),
+                (4, "FTA score 65.00 · cyclo 2 · cognitive 1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hover() {
+        let file_data = file_data(20.0, "OK", Severity::None);
+        let config = get_default_config();
+        let markdown = |line: u32| {
+            hover(TEXT, &file_data, line, &config).map(|hover| match hover.contents {
+                HoverContents::Markup(markup) => markup.value,
+                contents => panic!("Unexpected hover contents: {:?}", contents),
+            })
+        };
+
+        assert!(markdown(0)
+            .unwrap()
+            .starts_with("**src/cart.ts**: FTA score 20.00 (OK)\n\n| Metric | Value |"));
+        assert!(markdown(4)
+            .unwrap()
+            .starts_with("**checkout**: FTA score 65.00 (Needs improvement)"));
+        assert!(markdown(4)
+            .unwrap()
+            .contains("| Cyclomatic complexity | 2 |\n| Cognitive complexity | 1 |"));
+        assert_eq!(markdown(2), None);
+    }
+
+    fn receive(client: &Connection) -> Value {
+        match client.receiver.recv().unwrap() {
+            Message::Response(response) => response.result.unwrap(),
+            Message::Notification(notification) => notification.params,
+            Message::Request(request) => panic!("Unexpected request: {:?}", request),
+        }
+    }
+
+    fn request(client: &Connection, id: i32, method: &str, params: Value) -> Value {
+        client
+            .sender
+            .send(Message::Request(Request::new(
+                RequestId::from(id),
+                method.to_string(),
+                params,
+            )))
+            .unwrap();
+        receive(client)
+    }
+
+    fn notify(client: &Connection, method: &str, params: Value) {
+        client
+            .sender
+            .send(Message::Notification(Notification::new(
+                method.to_string(),
+                params,
+            )))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_language_server() {
+        let workspace = TempDir::new().unwrap();
+        fs::write(
+            workspace.path().join("fta.json"),
+            r#"{ "score_cap": 5, "overrides": [{ "files": ["src/generated/**"], "exclude_under": 100 }] }"#,
+        )
+        .unwrap();
+        let root = Url::from_directory_path(workspace.path()).unwrap();
+        let uri = root.join("src/cart.ts").unwrap();
+
+        let (server, client) = Connection::memory();
+        let server = thread::spawn(move || serve(server));
+
+        let initialized = request(
+            &client,
+            1,
+            "initialize",
+            json!({ "rootUri": root, "capabilities": {} }),
+        );
+        assert_eq!(initialized["capabilities"]["hoverProvider"], json!(true));
+        notify(&client, "initialized", json!({}));
+
+        let text = "function add(a: number, b: number) {\n  if (a > b) {\n    return a + b;\n  }\n  return b - a;\n}\nconsole.log(add(1, 2));\n";
+        notify(
+            &client,
+            "textDocument/didOpen",
+            json!({
+                "textDocument": { "uri": uri, "languageId": "typescript", "version": 1, "text": text }
+            }),
+        );
+        let published = receive(&client);
+        assert_eq!(published["uri"], json!(uri));
+        assert_eq!(published["diagnostics"][0]["code"], json!("fta/score-cap"));
+
+        // Overrides apply to `exclude_under` as well
+        let generated = root.join("src/generated/cart.ts").unwrap();
+        notify(
+            &client,
+            "textDocument/didOpen",
+            json!({
+                "textDocument": { "uri": generated, "languageId": "typescript", "version": 1, "text": text }
+            }),
+        );
+        let published = receive(&client);
+        assert_eq!(published["uri"], json!(generated));
+        assert_eq!(published["diagnostics"], json!([]));
+
+        // Edits that can't be parsed keep the previous analysis
+        notify(
+            &client,
+            "textDocument/didChange",
+            json!({
+                "textDocument": { "uri": uri, "version": 2 },
+                "contentChanges": [{ "text": "function add(" }]
+            }),
+        );
+
+        let code_lenses = request(
+            &client,
+            2,
+            "textDocument/codeLens",
+            json!({ "textDocument": { "uri": uri } }),
+        );
+        assert_eq!(code_lenses.as_array().unwrap().len(), 2);
+
+        let hovered = request(
+            &client,
+            3,
+            "textDocument/hover",
+            json!({ "textDocument": { "uri": uri }, "position": { "line": 0, "character": 10 } }),
+        );
+        assert!(hovered["contents"]["value"]
+            .as_str()
+            .unwrap()
+            .starts_with("**add**: FTA score"));
+
+        notify(
+            &client,
+            "textDocument/didClose",
+            json!({ "textDocument": { "uri": uri } }),
+        );
+        assert_eq!(receive(&client)["diagnostics"], json!([]));
+
+        assert_eq!(request(&client, 4, "shutdown", Value::Null), Value::Null);
+        notify(&client, "exit", Value::Null);
+        assert!(server.join().unwrap().is_ok());
+    }
+}
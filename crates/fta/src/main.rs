@@ -1,16 +1,86 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use fta::analyze;
+use fta::analyze_buffer;
+use fta::analyze_files;
+use fta::analyze_paths;
+use fta::baseline::{compare_to_baseline, read_baseline, write_baseline};
 use fta::config::read_config;
+use fta::error::FtaError;
+use fta::git::{changed_since, staged};
+use fta::group::GroupBy;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
 #[cfg(feature = "use_output")]
-use fta::output::generate_output;
+use fta::diff::{diff_reports, format_regressions, read_report};
+#[cfg(not(feature = "use_output"))]
+use fta::error::format_score_cap_breaches;
+#[cfg(feature = "use_output")]
+use fta::git::file_histories;
+#[cfg(feature = "use_output")]
+use fta::group::group_files;
+#[cfg(feature = "use_output")]
+use fta::history::history;
+#[cfg(feature = "use_output")]
+use fta::hotspots::find_hotspots;
+#[cfg(feature = "use_output")]
+use fta::output::{
+    format_failures_csv, generate_diff_output, generate_group_output, generate_history_output,
+    generate_hotspot_output, generate_output, generate_score_cap_summary,
+};
+#[cfg(feature = "use_output")]
+use fta::summary::summarize;
+#[cfg(feature = "watch")]
+use fta::watch::{format_score_deltas, watch};
+#[cfg(feature = "use_output")]
+use std::num::NonZeroUsize;
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Cli {
-    #[arg(required = true, help = "Path to the project to analyze")]
-    project: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[arg(
+        value_name = "PATH",
+        required_unless_present_any = ["stdin", "files_from"],
+        help = "Path to the project to analyze, or the files to analyze"
+    )]
+    paths: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Analyze source code read from stdin instead of files on disk",
+        requires = "stdin_filename",
+        conflicts_with_all = ["paths", "files_from", "changed_since", "staged", "write_baseline"]
+    )]
+    stdin: bool,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Path of the source code read from stdin, which determines its language and the overrides that apply to it",
+        requires = "stdin"
+    )]
+    stdin_filename: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Analyze the files listed in the given file, one per line, or - to read the list from stdin",
+        conflicts_with_all = ["changed_since", "staged", "write_baseline"]
+    )]
+    files_from: Option<String>,
 
     #[arg(long, short, help = "Path to config file")]
     config_path: Option<String>,
@@ -19,7 +89,7 @@ struct Cli {
         long,
         short,
         default_value = "table",
-        value_parser(["table", "csv", "json"]),
+        value_parser(["table", "csv", "json", "sarif", "markdown", "html"]),
         help = "Output format (default: table)",
         conflicts_with = "json"
     )]
@@ -55,28 +125,516 @@ struct Cli {
         help = "Minimum number of lines of code for files to be included in output (default: 6)"
     )]
     exclude_under: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Whether to list the functions of each file in the table output (default: false)"
+    )]
+    functions: bool,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Write the score of every analyzed file to a baseline file"
+    )]
+    write_baseline: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Only fail when files get worse than the given baseline file, or new files are beyond the score cap",
+        conflicts_with = "write_baseline"
+    )]
+    baseline: Option<String>,
+
+    #[arg(
+        long,
+        help = "How much a file's score may increase over the baseline before failing (default: 0)"
+    )]
+    baseline_tolerance: Option<f64>,
+
+    #[arg(
+        long,
+        short,
+        help = "Number of threads to analyze files with, 0 picks a number based on the available CPUs (default: 0)"
+    )]
+    threads: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "REF",
+        help = "Only analyze files that changed since the given git ref, including uncommitted changes",
+        conflicts_with_all = ["staged", "write_baseline"]
+    )]
+    changed_since: Option<String>,
+
+    #[arg(
+        long,
+        help = "Only analyze files with changes staged for the next commit",
+        conflicts_with = "write_baseline"
+    )]
+    staged: bool,
+
+    #[arg(
+        long,
+        value_name = "GROUPING",
+        help = "Report per-directory or per-package rollups instead of individual files: directory, directory:<depth> or package"
+    )]
+    group_by: Option<GroupBy>,
+
+    #[arg(
+        long,
+        help = "Include summary statistics in JSON and CSV output. JSON output becomes an object with the version, summary and files"
+    )]
+    summary: bool,
+
+    #[arg(
+        long,
+        help = "Fail when files can't be parsed, rather than leaving them out of the report"
+    )]
+    fail_on_parse_error: bool,
+
+    #[arg(
+        long,
+        help = "Analyze every file from scratch, without reading or writing the cache"
+    )]
+    no_cache: bool,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Directory to cache analysis results in (default: <project>/.fta-cache)",
+        conflicts_with = "no_cache"
+    )]
+    cache_dir: Option<String>,
+
+    #[cfg(feature = "watch")]
+    #[arg(
+        long,
+        help = "Keep running after the analysis, and print how the scores of files change as they are saved",
+        conflicts_with_all = ["json", "format", "baseline", "write_baseline", "changed_since", "staged", "fail_on_parse_error", "stdin", "files_from"]
+    )]
+    watch: bool,
 }
 
-pub fn main() {
-    // Start tracking execution time
-    let start = Instant::now();
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Work with fta.json config files
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Run a language server over stdio, for complexity feedback in editors
+    #[cfg(feature = "lsp")]
+    Lsp,
+    /// Rank files by how complex they are and how often they change in git
+    #[cfg(feature = "use_output")]
+    Hotspots {
+        #[arg(default_value = ".", help = "Path to the project to analyze")]
+        project: String,
 
-    let cli = Cli::parse();
+        #[arg(long, short, help = "Path to config file")]
+        config_path: Option<String>,
+
+        #[arg(
+            long,
+            default_value = "1 year ago",
+            help = "Only count commits since the given date, in any format git understands, e.g. 2024-01-01 or \"6 months ago\""
+        )]
+        since: String,
+
+        #[arg(
+            long,
+            default_value_t = 0.5,
+            value_parser = parse_weight,
+            help = "How much complexity counts towards the hotspot score, from 0 (churn only) to 1 (complexity only)"
+        )]
+        complexity_weight: f64,
+
+        #[arg(
+            long,
+            short,
+            default_value = "table",
+            value_parser(["table", "json"]),
+            help = "Output format (default: table)"
+        )]
+        format: String,
+
+        #[arg(
+            long,
+            short,
+            help = "Maximum number of files to include in the table output (default: 5000)"
+        )]
+        output_limit: Option<usize>,
+
+        #[arg(
+            long,
+            help = "Analyze every file from scratch, without reading or writing the cache"
+        )]
+        no_cache: bool,
+
+        #[arg(
+            long,
+            value_name = "DIR",
+            help = "Directory to cache analysis results in (default: <project>/.fta-cache)",
+            conflicts_with = "no_cache"
+        )]
+        cache_dir: Option<String>,
+    },
+    /// Analyze past commits and report how the scores changed over time
+    #[cfg(feature = "use_output")]
+    History {
+        #[arg(default_value = ".", help = "Path to the project to analyze")]
+        project: String,
+
+        #[arg(long, short, help = "Path to config file")]
+        config_path: Option<String>,
+
+        #[arg(
+            long,
+            default_value = "3 months ago",
+            help = "Analyze commits since the given date, in any format git understands, e.g. 2024-01-01 or \"6 months ago\""
+        )]
+        since: String,
+
+        #[arg(
+            long,
+            default_value = "1",
+            help = "Analyze every nth commit, counting back from the latest one"
+        )]
+        step: NonZeroUsize,
+
+        #[arg(
+            long,
+            short,
+            default_value = "table",
+            value_parser(["table", "csv", "json"]),
+            help = "Output format (default: table)"
+        )]
+        format: String,
+
+        #[arg(
+            long,
+            help = "Analyze every commit from scratch, rather than reusing the results of unchanged files"
+        )]
+        no_cache: bool,
+    },
+    /// Compare two JSON reports and show how the scores of each file changed
+    #[cfg(feature = "use_output")]
+    Diff {
+        #[arg(help = "Path to the old report, written with --format json")]
+        old: String,
+
+        #[arg(help = "Path to the new report, written with --format json")]
+        new: String,
+
+        #[arg(
+            long,
+            short,
+            default_value = "table",
+            value_parser(["table", "markdown", "json"]),
+            help = "Output format (default: table)"
+        )]
+        format: String,
+
+        #[arg(
+            long,
+            value_name = "TOLERANCE",
+            help = "Exit with a non-zero code if the FTA score of any file increased by more than the tolerance"
+        )]
+        fail_on_regression: Option<f64>,
+    },
+}
+
+#[cfg(feature = "use_output")]
+fn parse_weight(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(weight) if (0.0..=1.0).contains(&weight) => Ok(weight),
+        _ => Err("expected a number from 0 to 1".to_string()),
+    }
+}
 
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Check a config file without running the analysis
+    Validate {
+        #[arg(default_value = "fta.json", help = "Path to the config file")]
+        config_path: String,
+    },
+}
+
+fn run_command(command: Command) {
+    match command {
+        Command::Config {
+            command: ConfigCommand::Validate { config_path },
+        } => match read_config(config_path.clone(), true) {
+            Ok(_) => println!("{} is valid.", config_path),
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        },
+        #[cfg(feature = "lsp")]
+        Command::Lsp => {
+            if let Err(err) = fta::lsp::run() {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        }
+        #[cfg(feature = "use_output")]
+        Command::Hotspots {
+            project,
+            config_path,
+            since,
+            complexity_weight,
+            format,
+            output_limit,
+            no_cache,
+            cache_dir,
+        } => {
+            let start = Instant::now();
+            let mut config = read_project_config(&project, config_path);
+            if let Some(value) = output_limit {
+                config.output_limit = value;
+            }
+            if !no_cache {
+                config.cache_dir =
+                    Some(cache_dir.unwrap_or_else(|| format!("{}/.fta-cache", project)));
+            }
+
+            // Hotspots are about every complex file, regardless of the score cap
+            let report = match analyze(&project, &config) {
+                Ok(report) => report,
+                Err(FtaError::ScoreCapBreached { report, .. }) => *report,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            };
+            let histories = match file_histories(&project, &since) {
+                Ok(histories) => histories,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            };
+
+            let hotspots = find_hotspots(&report.files, &histories, complexity_weight);
+            let elapsed = start.elapsed().as_secs_f64();
+            println!(
+                "{}",
+                generate_hotspot_output(
+                    &hotspots,
+                    &format,
+                    &elapsed,
+                    config.output_limit,
+                    report.files.len(),
+                    &since
+                )
+            );
+        }
+        #[cfg(feature = "use_output")]
+        Command::History {
+            project,
+            config_path,
+            since,
+            step,
+            format,
+            no_cache,
+        } => {
+            let start = Instant::now();
+            let config = read_project_config(&project, config_path);
+
+            let result = history(
+                &project,
+                &config,
+                &since,
+                step.get(),
+                !no_cache,
+                |revision, index, total| {
+                    eprintln!(
+                        "Analyzing commit {}/{}: {} ({})",
+                        index + 1,
+                        total,
+                        revision.commit,
+                        revision.date
+                    );
+                },
+            );
+            match result {
+                Ok(series) => {
+                    let elapsed = start.elapsed().as_secs_f64();
+                    println!("{}", generate_history_output(&series, &format, &elapsed));
+                }
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        #[cfg(feature = "use_output")]
+        Command::Diff {
+            old,
+            new,
+            format,
+            fail_on_regression,
+        } => {
+            let (old, new) = match (read_report(&old), read_report(&new)) {
+                (Ok(old), Ok(new)) => (old, new),
+                (Err(err), _) | (_, Err(err)) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            };
+
+            let diff = diff_reports(&old, &new);
+            println!("{}", generate_diff_output(&diff, &format));
+
+            if let Some(tolerance) = fail_on_regression {
+                let regressions = diff.regressions(tolerance);
+                if !regressions.is_empty() {
+                    eprintln!("{}", format_regressions(&regressions, tolerance));
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+/// Reads the config of `project`, from the given path or its `fta.json` if there is one.
+fn read_project_config(project: &str, config_path: Option<String>) -> fta::FtaConfigResolved {
     // Resolve the fta.json path, which can optionally be used-supplied
-    let (config_path, path_specified_by_user) = match cli.config_path {
+    let (config_path, path_specified_by_user) = match config_path {
         Some(config_path_arg) => (config_path_arg, true),
-        None => (format!("{}/fta.json", cli.project), false),
+        None => (format!("{}/fta.json", project), false),
     };
 
     // Resolve the input config. Optionally adds fta.json values to the default config.
-    let mut config = match read_config(config_path, path_specified_by_user) {
+    match read_config(config_path, path_specified_by_user) {
         Ok(config) => config,
         Err(err) => {
             eprintln!("{}", err);
             std::process::exit(1);
         }
+    }
+}
+
+/// What to analyze: a whole project, or an explicit set of files. Explicit files are relative to
+/// the current directory, which is treated as the project.
+enum Input {
+    Project(String),
+    Files(HashSet<PathBuf>),
+    Stdin { file_name: String, source: String },
+}
+
+impl Input {
+    fn from_cli(cli: &Cli) -> Result<Input, String> {
+        if let Some(file_name) = &cli.stdin_filename {
+            let mut source = String::new();
+            io::stdin()
+                .read_to_string(&mut source)
+                .map_err(|err| format!("InputError! Unable to read stdin: {}", err))?;
+            return Ok(Input::Stdin {
+                file_name: file_name.clone(),
+                source,
+            });
+        }
+
+        // A single directory is a project, as it's always been
+        if let [project] = cli.paths.as_slice() {
+            if cli.files_from.is_none() && !Path::new(project).is_file() {
+                return Ok(Input::Project(project.clone()));
+            }
+        }
+
+        let mut files: HashSet<PathBuf> = cli.paths.iter().map(PathBuf::from).collect();
+        if let Some(list_path) = &cli.files_from {
+            let unable_to_read =
+                |err: io::Error| format!("InputError! Unable to read {}: {}", list_path, err);
+            let list = if list_path == "-" {
+                let mut list = String::new();
+                io::stdin()
+                    .read_to_string(&mut list)
+                    .map_err(unable_to_read)?;
+                list
+            } else {
+                fs::read_to_string(list_path).map_err(unable_to_read)?
+            };
+            files.extend(
+                list.lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(PathBuf::from),
+            );
+        }
+
+        Ok(Input::Files(files))
+    }
+
+    fn project(&self) -> String {
+        match self {
+            Input::Project(project) => project.clone(),
+            Input::Files(_) | Input::Stdin { .. } => ".".to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "watch")]
+fn run_watch(project: &String, config: &fta::FtaConfigResolved, findings: &[fta::FileData]) {
+    eprintln!("Watching {} for changes...", project);
+
+    let result = watch(project, config, findings, |deltas| {
+        println!("{}", format_score_deltas(deltas));
+    });
+
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+pub fn main() {
+    // Start tracking execution time
+    let start = Instant::now();
+
+    let cli = Cli::parse();
+
+    if let Some(command) = cli.command {
+        run_command(command);
+        return;
+    }
+    let input = match Input::from_cli(&cli) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
     };
+    let project = input.project();
+
+    let changes_requested = cli.changed_since.is_some() || cli.staged;
+    #[cfg(feature = "watch")]
+    let changes_requested = changes_requested || cli.watch;
+    if changes_requested && !matches!(input, Input::Project(_)) {
+        <Cli as clap::CommandFactory>::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--changed-since, --staged and --watch require a single project directory",
+            )
+            .exit();
+    }
+
+    #[cfg(feature = "use_output")]
+    if cli.group_by.is_some() && matches!(cli.format.as_str(), "sarif" | "markdown" | "html") {
+        <Cli as clap::CommandFactory>::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                format!("--group-by can't be used with {} output", cli.format),
+            )
+            .exit();
+    }
+
+    let mut config = read_project_config(&project, cli.config_path.clone());
 
     // Override config with CLI args where allowed + values are provided
     if let Some(value) = cli.output_limit {
@@ -91,9 +649,80 @@ pub fn main() {
     if let Some(value) = cli.exclude_under {
         config.exclude_under = value;
     }
+    if let Some(value) = cli.baseline_tolerance {
+        config.baseline_tolerance = value;
+    }
+    if let Some(value) = cli.threads {
+        config.threads = value;
+    }
+    // Source code from stdin isn't a file the cache could be kept up to date for
+    if !cli.no_cache && !cli.stdin {
+        config.cache_dir = Some(
+            cli.cache_dir
+                .unwrap_or_else(|| format!("{}/.fta-cache", project)),
+        );
+    }
+
+    // Optionally restrict the analysis to files changed according to git, or to explicit files
+    let changed_files = match &input {
+        Input::Project(_) if cli.changed_since.is_some() || cli.staged => {
+            let changed_files = match &cli.changed_since {
+                Some(git_ref) => changed_since(&project, git_ref),
+                None => staged(&project),
+            };
+            match changed_files {
+                Ok(changed_files) => Some(changed_files),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Input::Project(_) => None,
+        Input::Files(files) => Some(files.clone()),
+        Input::Stdin { file_name, .. } => Some(HashSet::from([PathBuf::from(file_name)])),
+    };
+
+    let baseline = cli.baseline.as_ref().map(|path| match read_baseline(path) {
+        Ok(mut baseline) => {
+            // Files outside of the changed set weren't analyzed, rather than removed
+            if let Some(changed_files) = &changed_files {
+                baseline
+                    .files
+                    .retain(|file_name, _| changed_files.contains(Path::new(file_name)));
+            }
+            baseline
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    });
 
     // Execute the analysis
-    let mut findings = analyze(&cli.project, &config);
+    let report = match (&input, &changed_files) {
+        (Input::Stdin { file_name, source }, _) => analyze_buffer(file_name, source, &config),
+        (Input::Files(files), _) => analyze_files(&project, &config, files),
+        (Input::Project(_), Some(changed_files)) => analyze_paths(&project, &config, changed_files),
+        (Input::Project(_), None) => analyze(&project, &config),
+    };
+    // Files beyond the score cap fail the run, but only after the full report has been printed.
+    // In baseline mode, existing files may be beyond the score cap as long as they don't get worse.
+    // The cap is instead applied to new files when comparing against the baseline.
+    let (report, score_cap_breaches) = match report {
+        Ok(report) => (report, Vec::new()),
+        Err(FtaError::ScoreCapBreached { report, .. }) if baseline.is_some() => {
+            (*report, Vec::new())
+        }
+        Err(FtaError::ScoreCapBreached {
+            breaches, report, ..
+        }) => (*report, breaches),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+    let mut findings = report.files;
 
     // Sort the result for display
     findings.sort_unstable_by(|a, b| b.fta_score.partial_cmp(&a.fta_score).unwrap());
@@ -101,19 +730,94 @@ pub fn main() {
     // Execution finished, capture elapsed time
     let elapsed = start.elapsed().as_secs_f64();
     #[cfg(feature = "use_output")]
+    let format = if cli.json {
+        "json".to_string()
+    } else {
+        cli.format
+    };
+    #[cfg(feature = "use_output")]
     {
+        // Tables and SARIF always include the summary, while it changes the shape of JSON and CSV
+        let summary = summarize(&findings, &report.failures, elapsed, &config);
+        let summary =
+            (cli.summary || !matches!(format.as_str(), "json" | "csv")).then_some(&summary);
+
         // Format and display the results
-        let output = generate_output(
-            &findings,
-            if cli.json {
-                "json".to_string()
-            } else {
-                cli.format
-            },
-            &elapsed,
-            config.output_limit,
-        );
+        let mut output = match cli.group_by {
+            Some(group_by) => generate_group_output(
+                &group_files(&project, &findings, group_by),
+                &format,
+                &elapsed,
+                config.output_limit,
+                findings.len(),
+                summary,
+            ),
+            None => generate_output(
+                &findings,
+                format.clone(),
+                &elapsed,
+                config.output_limit,
+                cli.functions,
+                &config,
+                summary,
+            ),
+        };
+        // The summary lists the files that couldn't be parsed, which CSV output always includes
+        if format == "csv" && summary.is_none() && !report.failures.is_empty() {
+            output.push_str(&format!("\n\n{}", format_failures_csv(&report.failures)));
+        }
 
         println!("{}", output);
     }
+
+    #[cfg(feature = "watch")]
+    if cli.watch {
+        run_watch(&project, &config, &findings);
+    }
+
+    if let Some(path) = cli.write_baseline {
+        if let Err(err) = write_baseline(&path, &findings) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(baseline) = baseline {
+        let comparison =
+            compare_to_baseline(&baseline, &findings, config.baseline_tolerance, &config);
+        eprintln!("{}", comparison.report());
+        if comparison.has_failures() {
+            std::process::exit(1);
+        }
+    }
+
+    if cli.fail_on_parse_error && !report.failures.is_empty() {
+        eprintln!(
+            "ParseError! {} file(s) could not be parsed:",
+            report.failures.len()
+        );
+        for failure in &report.failures {
+            eprintln!(
+                "  {}:{}:{}: {}",
+                failure.file_name, failure.line, failure.column, failure.message
+            );
+        }
+        if score_cap_breaches.is_empty() {
+            std::process::exit(1);
+        }
+    }
+
+    if !score_cap_breaches.is_empty() {
+        #[cfg(feature = "use_output")]
+        eprintln!(
+            "{}",
+            generate_score_cap_summary(&score_cap_breaches, config.score_cap, &format)
+        );
+        #[cfg(not(feature = "use_output"))]
+        eprintln!(
+            "{}",
+            format_score_cap_breaches(&score_cap_breaches, config.score_cap)
+        );
+        std::process::exit(1);
+    }
 }
@@ -1,39 +1,955 @@
-use crate::structs::FileData;
+use crate::config::{assessment_bands, resolve_for_file};
+use crate::diff::{FileDiff, ReportDiff};
+use crate::error::{format_score_cap_breaches, ParseFailure, ScoreCapBreach};
+use crate::group::{group_files, GroupBy, GroupData};
+use crate::history::RevisionScores;
+use crate::hotspots::Hotspot;
+use crate::structs::{FileData, FtaConfigResolved, Severity, Suppression};
+use crate::summary::{ScoreStatistics, Summary};
+use crate::utils::get_assessment;
 use comfy_table::{presets::UTF8_FULL, Table};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
 
 mod tests;
 
+/// Keeps the end of `input`, at most `max_length` characters including the leading ellipsis.
 pub fn truncate_string(input: &str, max_length: usize) -> String {
-    if input.len() <= max_length {
-        input.to_string()
+    if input.chars().count() <= max_length {
+        return input.to_string();
+    }
+
+    match max_length.saturating_sub(3).checked_sub(1) {
+        Some(last) => {
+            let (start, _) = input.char_indices().rev().nth(last).unwrap();
+            format!("...{}", &input[start..])
+        }
+        None => "...".to_string(),
+    }
+}
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SCORE_CAP_RULE: &str = "fta/score-cap";
+const ASSESSMENT_RULE: &str = "fta/assessment";
+
+fn sarif_rules(config: &FtaConfigResolved) -> Value {
+    let bands = assessment_bands(config)
+        .into_iter()
+        .filter_map(|band| {
+            sarif_level(band.severity).map(|level| format!("\"{}\" ({})", band.label, level))
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    json!([
+        {
+            "id": SCORE_CAP_RULE,
+            "name": "FileExceedsScoreCap",
+            "shortDescription": { "text": "File exceeds the FTA score cap" },
+            "fullDescription": {
+                "text": format!("The FTA score of the file is above the configured score_cap of {}.", config.score_cap)
+            },
+            "helpUri": "https://ftaproject.dev/docs/configuration",
+            "defaultConfiguration": { "level": "error" }
+        },
+        {
+            "id": ASSESSMENT_RULE,
+            "name": "FileComplexity",
+            "shortDescription": { "text": "File is complex and may be hard to maintain" },
+            "fullDescription": {
+                "text": format!("The FTA score of the file is in one of the following assessment bands: {}.", bands)
+            },
+            "helpUri": "https://ftaproject.dev/docs/scoring",
+            "defaultConfiguration": { "level": "warning" }
+        }
+    ])
+}
+
+/// SARIF levels for each severity. Files without a severity don't produce a result.
+fn sarif_level(severity: Severity) -> Option<&'static str> {
+    match severity {
+        Severity::Error => Some("error"),
+        Severity::Warning => Some("warning"),
+        Severity::Note => Some("note"),
+        Severity::None => None,
+    }
+}
+
+fn sarif_result(file_data: &FileData, rule_id: &str, level: &str, message: String) -> Value {
+    json!({
+        "ruleId": rule_id,
+        "ruleIndex": if rule_id == SCORE_CAP_RULE { 0 } else { 1 },
+        "level": level,
+        "message": { "text": message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": {
+                    "uri": file_data.file_name.replace('\\', "/").replace(' ', "%20"),
+                    "uriBaseId": "%SRCROOT%"
+                }
+            }
+        }],
+        "properties": {
+            "fta_score": file_data.fta_score,
+            "cyclo": file_data.cyclo,
+            "cognitive": file_data.cognitive,
+            "line_count": file_data.line_count,
+            "assessment": file_data.assessment
+        }
+    })
+}
+
+/// Marks a result as suppressed by an inline comment.
+fn sarif_suppressed(mut result: Value, suppression: &Option<Suppression>) -> Value {
+    if let Some(suppression) = suppression {
+        result["suppressions"] = json!([{
+            "kind": "inSource",
+            "justification": suppression.to_string()
+        }]);
+    }
+    result
+}
+
+fn generate_sarif(
+    file_data_list: &[FileData],
+    config: &FtaConfigResolved,
+    summary: Option<&Summary>,
+) -> String {
+    let mut results = Vec::new();
+
+    for file_data in file_data_list {
+        let configured_score_cap = resolve_for_file(config, &file_data.file_name).score_cap;
+        let score_cap_result = |score_cap: usize| {
+            sarif_result(
+                file_data,
+                SCORE_CAP_RULE,
+                "error",
+                format!(
+                    "File has an FTA score of {:.2}, which is beyond the score cap of {}.",
+                    file_data.fta_score, score_cap
+                ),
+            )
+        };
+        // Files within the score cap of an inline comment are still reported, but suppressed
+        match file_data.score_cap(configured_score_cap) {
+            Some(score_cap) if file_data.fta_score > score_cap as f64 => {
+                results.push(score_cap_result(score_cap));
+            }
+            _ if file_data.fta_score > configured_score_cap as f64 => {
+                results.push(sarif_suppressed(
+                    score_cap_result(configured_score_cap),
+                    &file_data.suppression,
+                ));
+            }
+            _ => {}
+        }
+
+        if let Some(level) = sarif_level(file_data.severity) {
+            let result = sarif_result(
+                file_data,
+                ASSESSMENT_RULE,
+                level,
+                format!(
+                    "File has an FTA score of {:.2} ({}).",
+                    file_data.fta_score, file_data.assessment
+                ),
+            );
+            results.push(if file_data.is_ignored() {
+                sarif_suppressed(result, &file_data.suppression)
+            } else {
+                result
+            });
+        }
+    }
+
+    let mut log = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "fta",
+                    "fullName": "Fast TypeScript Analyzer",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "semanticVersion": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://ftaproject.dev",
+                    "rules": sarif_rules(config)
+                }
+            },
+            "results": results
+        }]
+    });
+    if let Some(summary) = summary {
+        log["runs"][0]["properties"] = json!({ "summary": summary });
+        log["runs"][0]["invocations"] = json!([{
+            "executionSuccessful": true,
+            "toolExecutionNotifications": summary.failures.iter().map(|failure| json!({
+                "level": "error",
+                "message": { "text": format!("Unable to parse the file: {}", failure.message) },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": failure.file_name.replace('\\', "/").replace(' ', "%20"),
+                            "uriBaseId": "%SRCROOT%"
+                        },
+                        "region": {
+                            "startLine": failure.line,
+                            "startColumn": failure.column
+                        }
+                    }
+                }]
+            })).collect::<Vec<_>>()
+        }]);
+    }
+
+    serde_json::to_string_pretty(&log).unwrap()
+}
+
+const HISTOGRAM_BAR_WIDTH: usize = 30;
+
+fn format_summary_table(summary: &Summary) -> String {
+    let mut lines = vec!["Summary:".to_string()];
+
+    let mut files = format!("  Files: {}", summary.file_count);
+    if !summary.failures.is_empty() {
+        files.push_str(&format!(
+            " ({} could not be parsed)",
+            summary.failures.len()
+        ));
+    }
+    lines.push(files);
+    lines.push(format!("  Lines: {}", summary.line_count));
+    if let Some(scores) = &summary.scores {
+        lines.push(format!(
+            "  FTA Score: mean {:.2}, median {:.2}, p75 {:.2}, p90 {:.2}, p95 {:.2}, max {:.2}",
+            scores.mean, scores.median, scores.p75, scores.p90, scores.p95, scores.max
+        ));
+    }
+    lines.push(format!(
+        "  Assessments: {}",
+        summary
+            .assessments
+            .iter()
+            .map(|count| format!("{} {}", count.assessment, count.count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+
+    lines.push("  Distribution:".to_string());
+    let largest_bucket = summary
+        .histogram
+        .iter()
+        .map(|bucket| bucket.count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    for bucket in &summary.histogram {
+        let range = match bucket.max {
+            Some(max) => format!("{}-{}", bucket.min, max),
+            None => format!("{}+", bucket.min),
+        };
+        let bar = "█".repeat(bucket.count * HISTOGRAM_BAR_WIDTH / largest_bucket);
+        lines.push(format!("    {:>7}  {} {}", range, bar, bucket.count));
+    }
+
+    if !summary.failures.is_empty() {
+        lines.push("  Failed to parse:".to_string());
+        for failure in &summary.failures {
+            lines.push(format!(
+                "    {}:{}:{}: {}",
+                failure.file_name, failure.line, failure.column, failure.message
+            ));
+        }
+    }
+
+    if !summary.suppressed.is_empty() {
+        lines.push("  Suppressed:".to_string());
+        for suppressed in &summary.suppressed {
+            let mut line = format!(
+                "    {}:{}: {}",
+                suppressed.file_name,
+                suppressed.suppression.line(),
+                suppressed.suppression
+            );
+            if let Some(function) = &suppressed.function {
+                line.push_str(&format!(", for {}", function));
+            }
+            lines.push(line);
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// The assessment shown in tables, noting any suppression.
+fn table_assessment(assessment: String, suppression: &Option<Suppression>) -> String {
+    match suppression {
+        Some(Suppression::IgnoreFile { .. }) | Some(Suppression::IgnoreFunction { .. }) => {
+            format!("{} (ignored)", assessment)
+        }
+        Some(Suppression::ScoreCap { score_cap, .. }) => {
+            format!("{} (score cap {})", assessment, score_cap)
+        }
+        None => assessment,
+    }
+}
+
+/// A badge for the severity of an assessment, to tell the assessments apart at a glance.
+fn severity_badge(severity: Severity) -> &'static str {
+    match severity {
+        Severity::None => "🟢",
+        Severity::Note => "🔵",
+        Severity::Warning => "🟡",
+        Severity::Error => "🔴",
+    }
+}
+
+fn format_summary_markdown(summary: &Summary) -> String {
+    let mut lines = vec![format!(
+        "**{} files**, {} lines, analyzed in {}s.",
+        summary.file_count,
+        summary.line_count,
+        (summary.elapsed * 10000.0).round() / 10000.0
+    )];
+    if let Some(scores) = &summary.scores {
+        lines.push(String::new());
+        lines.push("| Mean | Median | P75 | P90 | P95 | Max |".to_string());
+        lines.push("| ---: | ---: | ---: | ---: | ---: | ---: |".to_string());
+        lines.push(format!(
+            "| {:.2} | {:.2} | {:.2} | {:.2} | {:.2} | {:.2} |",
+            scores.mean, scores.median, scores.p75, scores.p90, scores.p95, scores.max
+        ));
+    }
+    lines.push(String::new());
+    lines.push(
+        summary
+            .assessments
+            .iter()
+            .map(|count| {
+                format!(
+                    "{} {}: {}",
+                    severity_badge(count.severity),
+                    count.assessment,
+                    count.count
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" · "),
+    );
+
+    if !summary.failures.is_empty() {
+        lines.push(String::new());
+        lines.push(format!(
+            "⚠️ {} file(s) could not be parsed:",
+            summary.failures.len()
+        ));
+        lines.push(String::new());
+        for failure in &summary.failures {
+            lines.push(format!(
+                "- `{}:{}:{}`: {}",
+                failure.file_name, failure.line, failure.column, failure.message
+            ));
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Renders the report as GitHub-flavoured markdown, to be posted as a pull request comment. The
+/// files beyond `output_limit` are listed in a collapsed section rather than left out.
+fn generate_markdown(
+    file_data_list: &[FileData],
+    elapsed: &f64,
+    output_limit: usize,
+    show_functions: bool,
+    config: &FtaConfigResolved,
+    summary: Option<&Summary>,
+) -> String {
+    let header = "| File | Num. lines | Cognitive | FTA Score | Assessment |\n| --- | ---: | ---: | ---: | --- |";
+    let rows = |files: &[FileData]| {
+        let mut rows = vec![header.to_string()];
+        for file_data in files {
+            rows.push(format!(
+                "| `{}` | {} | {} | {:.2} | {} {} |",
+                markdown_escape(&file_data.file_name),
+                file_data.line_count,
+                file_data.cognitive,
+                file_data.fta_score,
+                severity_badge(file_data.severity),
+                table_assessment(file_data.assessment.clone(), &file_data.suppression)
+            ));
+
+            if show_functions {
+                let bands = assessment_bands(&resolve_for_file(config, &file_data.file_name));
+                let mut functions = file_data.functions.iter().collect::<Vec<_>>();
+                functions.sort_by(|a, b| b.fta_score.partial_cmp(&a.fta_score).unwrap());
+
+                for function in functions {
+                    let (assessment, severity) = get_assessment(function.fta_score, &bands);
+                    rows.push(format!(
+                        "| ↳ `{}`:{}-{} | {} | {} | {:.2} | {} {} |",
+                        markdown_escape(&function.name),
+                        function.start_line,
+                        function.end_line,
+                        function.line_count,
+                        function.cognitive,
+                        function.fta_score,
+                        severity_badge(severity),
+                        table_assessment(assessment, &function.suppression)
+                    ));
+                }
+            }
+        }
+        rows.join("\n")
+    };
+
+    let mut sections = vec![
+        "## FTA report".to_string(),
+        match summary {
+            Some(summary) => format_summary_markdown(summary),
+            None => format!(
+                "**{} files** analyzed in {}s.",
+                file_data_list.len(),
+                (elapsed * 10000.0).round() / 10000.0
+            ),
+        },
+    ];
+
+    let shown = output_limit.min(file_data_list.len());
+    if shown > 0 {
+        sections.push(rows(&file_data_list[..shown]));
+    }
+    if shown < file_data_list.len() {
+        let remaining = &file_data_list[shown..];
+        sections.push(format!(
+            "<details>\n<summary>{} more file(s)</summary>\n\n{}\n\n</details>",
+            remaining.len(),
+            rows(remaining)
+        ));
+    }
+
+    sections.join("\n\n")
+}
+
+const HTML_REPORT_TEMPLATE: &str = include_str!("report.html");
+
+/// Renders the report as a single HTML page with no external dependencies, for sharing it beyond
+/// the terminal. Every file is included, as the page has a treemap and a table that can be
+/// filtered, along with rollups by directory.
+fn generate_html(
+    file_data_list: &[FileData],
+    config: &FtaConfigResolved,
+    summary: Option<&Summary>,
+) -> String {
+    // Scores are coloured from green to red, reaching red at the highest assessment band
+    let score_scale = assessment_bands(config)
+        .iter()
+        .filter_map(|band| band.above)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let data = json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "score_scale": if score_scale > 0.0 { score_scale } else { 100.0 },
+        "summary": summary,
+        "files": file_data_list,
+        "directories": group_files(".", file_data_list, GroupBy::Directory { depth: None }),
+    });
+
+    // The data is embedded in a script element, which mustn't be closed by any file name
+    let data = serde_json::to_string(&data)
+        .unwrap()
+        .replace('<', "\\u003c");
+    HTML_REPORT_TEMPLATE.replace("/*FTA_DATA*/", &data)
+}
+
+/// Quotes a CSV field if it contains separators or quotes.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
     } else {
-        format!("...{}", &input[input.len() - max_length + 3..])
+        value.to_string()
+    }
+}
+
+/// Lists the files that couldn't be parsed as a CSV section. It's part of the CSV summary, and
+/// follows the report on its own without one.
+pub fn format_failures_csv(failures: &[ParseFailure]) -> String {
+    let mut rows = vec!["Failed file,Line,Column,Message".to_string()];
+    for failure in failures {
+        rows.push(format!(
+            "{},{},{},{}",
+            csv_field(&failure.file_name),
+            failure.line,
+            failure.column,
+            csv_field(&failure.message)
+        ));
     }
+
+    rows.join("\n")
 }
 
+fn format_summary_csv(summary: &Summary) -> String {
+    let mut rows = vec![
+        "Summary,Value".to_string(),
+        format!("Files,{}", summary.file_count),
+        format!("Num. lines,{}", summary.line_count),
+        format!("Parse failures,{}", summary.failures.len()),
+        format!(
+            "Elapsed (s),{}",
+            (summary.elapsed * 10000.0).round() / 10000.0
+        ),
+    ];
+    if let Some(scores) = &summary.scores {
+        rows.push(format!("Mean FTA Score,{:.2}", scores.mean));
+        rows.push(format!("Median FTA Score,{:.2}", scores.median));
+        rows.push(format!("P75 FTA Score,{:.2}", scores.p75));
+        rows.push(format!("P90 FTA Score,{:.2}", scores.p90));
+        rows.push(format!("P95 FTA Score,{:.2}", scores.p95));
+        rows.push(format!("Max FTA Score,{:.2}", scores.max));
+    }
+    for bucket in &summary.histogram {
+        match bucket.max {
+            Some(max) => rows.push(format!("FTA Score {}-{},{}", bucket.min, max, bucket.count)),
+            None => rows.push(format!("FTA Score {}+,{}", bucket.min, bucket.count)),
+        }
+    }
+    for count in &summary.assessments {
+        rows.push(format!("{},{}", csv_field(&count.assessment), count.count));
+    }
+
+    if !summary.failures.is_empty() {
+        rows.push(format!("\n{}", format_failures_csv(&summary.failures)));
+    }
+
+    if !summary.suppressed.is_empty() {
+        rows.push("\nSuppressed file,Line,Function,Suppression".to_string());
+        for suppressed in &summary.suppressed {
+            rows.push(format!(
+                "{},{},{},{}",
+                csv_field(&suppressed.file_name),
+                suppressed.suppression.line(),
+                csv_field(suppressed.function.as_deref().unwrap_or_default()),
+                csv_field(&suppressed.suppression.to_string())
+            ));
+        }
+    }
+
+    rows.join("\n")
+}
+
+/// Wraps JSON output in an object with the FTA version and the summary, if there is one. The
+/// object is assembled by hand to keep the fields of the items in their usual order.
+fn json_envelope<T: serde::Serialize + ?Sized>(
+    key: &str,
+    items: &T,
+    summary: Option<&Summary>,
+) -> String {
+    let items = serde_json::to_string(items).unwrap();
+    match summary {
+        Some(summary) => format!(
+            "{{\"version\":{},\"summary\":{},\"{}\":{}}}",
+            json!(env!("CARGO_PKG_VERSION")),
+            serde_json::to_string(summary).unwrap(),
+            key,
+            items
+        ),
+        None => items,
+    }
+}
+
+/// Lists the files beyond the score cap once the report has been printed. JSON output gets a
+/// machine-readable summary so that it can be picked up alongside the report.
+pub fn generate_score_cap_summary(
+    breaches: &[ScoreCapBreach],
+    score_cap: usize,
+    format: &str,
+) -> String {
+    match format {
+        "json" => json!({
+            "score_cap": score_cap,
+            "breach_count": breaches.len(),
+            "breaches": breaches
+        })
+        .to_string(),
+        _ => format_score_cap_breaches(breaches, score_cap),
+    }
+}
+
+/// Renders directory or package rollups in place of the per-file report. SARIF results point at
+/// files, so grouped output isn't available as SARIF.
+pub fn generate_group_output(
+    groups: &[GroupData],
+    format: &str,
+    elapsed: &f64,
+    output_limit: usize,
+    file_count: usize,
+    summary: Option<&Summary>,
+) -> String {
+    let mut output = String::new();
+
+    match format {
+        "json" => {
+            output = json_envelope("groups", groups, summary);
+        }
+        "csv" => {
+            output.push_str(
+                "Group,Files,Num. lines,Mean FTA Score,Median FTA Score,P90 FTA Score,Max FTA Score,Halstead Effort,Halstead Bugs",
+            );
+            for group in groups {
+                output.push_str(&format!(
+                    "\n{},{},{},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2}",
+                    csv_field(&group.group),
+                    group.file_count,
+                    group.line_count,
+                    group.mean_score,
+                    group.median_score,
+                    group.p90_score,
+                    group.max_score,
+                    group.effort,
+                    group.bugs
+                ));
+            }
+            if let Some(summary) = summary {
+                output.push_str(&format!("\n\n{}", format_summary_csv(summary)));
+            }
+        }
+        "table" => {
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL);
+            table.set_content_arrangement(comfy_table::ContentArrangement::Dynamic);
+            table.force_no_tty();
+            table.set_width(100);
+            table.set_header(vec![
+                "Group",
+                "Files",
+                "Num. lines",
+                "Mean",
+                "Median",
+                "P90",
+                "Max",
+                "Effort",
+                "Bugs",
+            ]);
+
+            for group in groups.iter().take(output_limit) {
+                table.add_row(vec![
+                    truncate_string(&group.group, 40),
+                    group.file_count.to_string(),
+                    group.line_count.to_string(),
+                    format!("{:.2}", group.mean_score),
+                    format!("{:.2}", group.median_score),
+                    format!("{:.2}", group.p90_score),
+                    format!("{:.2}", group.max_score),
+                    format!("{:.0}", group.effort),
+                    format!("{:.2}", group.bugs),
+                ]);
+            }
+
+            output = format!(
+                "{}\n{}{} files analyzed in {}s, grouped into {} groups.",
+                table,
+                summary
+                    .map(|summary| format!("{}\n", format_summary_table(summary)))
+                    .unwrap_or_default(),
+                file_count,
+                (elapsed * 10000.0).round() / 10000.0,
+                groups.len()
+            );
+        }
+        _ => output.push_str("No output format specified."),
+    }
+
+    output
+}
+
+/// Renders the ranked hotspots of `fta hotspots` as a table or JSON.
+pub fn generate_hotspot_output(
+    hotspots: &[Hotspot],
+    format: &str,
+    elapsed: &f64,
+    output_limit: usize,
+    file_count: usize,
+    since: &str,
+) -> String {
+    match format {
+        "json" => serde_json::to_string(hotspots).unwrap(),
+        "table" => {
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL);
+            table.set_content_arrangement(comfy_table::ContentArrangement::Dynamic);
+            table.force_no_tty();
+            table.set_width(100);
+            table.set_header(vec![
+                "File",
+                "Hotspot score",
+                "FTA Score",
+                "Commits",
+                "Authors",
+                "Last modified",
+                "Assessment",
+            ]);
+
+            for hotspot in hotspots.iter().take(output_limit) {
+                table.add_row(vec![
+                    truncate_string(&hotspot.file_name, 40),
+                    format!("{:.2}", hotspot.hotspot_score),
+                    format!("{:.2}", hotspot.fta_score),
+                    hotspot.commits.to_string(),
+                    hotspot.authors.to_string(),
+                    hotspot.last_modified.clone(),
+                    hotspot.assessment.clone(),
+                ]);
+            }
+
+            format!(
+                "{}\n{} files analyzed in {}s, {} changed since {}.",
+                table,
+                file_count,
+                (elapsed * 10000.0).round() / 10000.0,
+                hotspots.len(),
+                since
+            )
+        }
+        _ => "No output format specified.".to_string(),
+    }
+}
+
+/// Renders the comparison of `fta diff`. Tables and markdown list the files that changed, followed
+/// by the added and removed files.
+pub fn generate_diff_output(diff: &ReportDiff, format: &str) -> String {
+    let header = vec![
+        "File",
+        "FTA Score",
+        "Δ FTA Score",
+        "Δ Cyclo",
+        "Δ Cognitive",
+        "Δ Lines",
+        "Δ Volume",
+        "Δ Bugs",
+    ];
+    let row = |file_diff: &FileDiff| {
+        vec![
+            format!("{:.2} -> {:.2}", file_diff.old_score, file_diff.new_score),
+            format!("{:+.2}", file_diff.delta.fta_score),
+            format!("{:+}", file_diff.delta.cyclo),
+            format!("{:+}", file_diff.delta.cognitive),
+            format!("{:+}", file_diff.delta.line_count),
+            format!("{:+.2}", file_diff.delta.halstead.volume),
+            format!("{:+.2}", file_diff.delta.halstead.bugs),
+        ]
+    };
+    let totals = format!(
+        "{} changed, {} added, {} removed and {} unchanged file(s).",
+        diff.changed.len(),
+        diff.added.len(),
+        diff.removed.len(),
+        diff.unchanged_count
+    );
+
+    match format {
+        "json" => serde_json::to_string(diff).unwrap(),
+        "markdown" => {
+            let mut lines = vec![
+                format!("| {} |", header.join(" | ")),
+                format!("| --- |{}", " ---: |".repeat(header.len() - 1)),
+            ];
+            for file_diff in &diff.changed {
+                lines.push(format!(
+                    "| `{}` | {} |",
+                    markdown_escape(&file_diff.file_name),
+                    row(file_diff).join(" | ")
+                ));
+            }
+            for (title, files) in [
+                ("Added files", &diff.added),
+                ("Removed files", &diff.removed),
+            ] {
+                if !files.is_empty() {
+                    lines.push(format!("\n**{}**\n", title));
+                    for file in files {
+                        lines.push(format!(
+                            "- `{}` ({:.2})",
+                            markdown_escape(&file.file_name),
+                            file.fta_score
+                        ));
+                    }
+                }
+            }
+            lines.push(format!("\n{}", totals));
+            lines.join("\n")
+        }
+        "table" => {
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL);
+            table.set_content_arrangement(comfy_table::ContentArrangement::Dynamic);
+            table.force_no_tty();
+            table.set_width(100);
+            table.set_header(header);
+            for file_diff in &diff.changed {
+                let mut cells = vec![truncate_string(&file_diff.file_name, 40)];
+                cells.extend(row(file_diff));
+                table.add_row(cells);
+            }
+
+            let mut output = table.to_string();
+            for (title, files) in [
+                ("Added files", &diff.added),
+                ("Removed files", &diff.removed),
+            ] {
+                if !files.is_empty() {
+                    output.push_str(&format!("\n{}:", title));
+                    for file in files {
+                        output.push_str(&format!("\n  {}: {:.2}", file.file_name, file.fta_score));
+                    }
+                }
+            }
+            format!("{}\n{}", output, totals)
+        }
+        _ => "No output format specified.".to_string(),
+    }
+}
+
+fn markdown_escape(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+/// Renders the time series of `fta history`. Tables only show the aggregate scores of each commit,
+/// while CSV has a column for the score of every file that existed at any of the commits.
+pub fn generate_history_output(series: &[RevisionScores], format: &str, elapsed: &f64) -> String {
+    let statistic = |revision: &RevisionScores, statistic: fn(&ScoreStatistics) -> f64| {
+        revision
+            .scores
+            .as_ref()
+            .map(|scores| format!("{:.2}", statistic(scores)))
+            .unwrap_or_default()
+    };
+
+    match format {
+        "json" => serde_json::to_string(series).unwrap(),
+        "csv" => {
+            let file_names: BTreeSet<&str> = series
+                .iter()
+                .flat_map(|revision| revision.files.iter())
+                .map(|file| file.file_name.as_str())
+                .collect();
+
+            let mut output = String::from(
+                "Commit,Date,Files,Num. lines,Mean FTA Score,Median FTA Score,Max FTA Score",
+            );
+            for file_name in &file_names {
+                output.push_str(&format!(",{}", csv_field(file_name)));
+            }
+            for revision in series {
+                output.push_str(&format!(
+                    "\n{},{},{},{},{},{},{}",
+                    revision.commit,
+                    revision.date,
+                    revision.file_count,
+                    revision.line_count,
+                    statistic(revision, |scores| scores.mean),
+                    statistic(revision, |scores| scores.median),
+                    statistic(revision, |scores| scores.max)
+                ));
+                for file_name in &file_names {
+                    let score = revision
+                        .files
+                        .iter()
+                        .find(|file| file.file_name == *file_name)
+                        .map(|file| format!("{:.2}", file.fta_score));
+                    output.push_str(&format!(",{}", score.unwrap_or_default()));
+                }
+            }
+            output
+        }
+        "table" => {
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL);
+            table.set_content_arrangement(comfy_table::ContentArrangement::Dynamic);
+            table.force_no_tty();
+            table.set_width(100);
+            table.set_header(vec![
+                "Commit",
+                "Date",
+                "Files",
+                "Num. lines",
+                "Mean",
+                "Median",
+                "Max",
+            ]);
+
+            for revision in series {
+                table.add_row(vec![
+                    revision.commit.chars().take(10).collect(),
+                    revision.date.clone(),
+                    revision.file_count.to_string(),
+                    revision.line_count.to_string(),
+                    statistic(revision, |scores| scores.mean),
+                    statistic(revision, |scores| scores.median),
+                    statistic(revision, |scores| scores.max),
+                ]);
+            }
+
+            format!(
+                "{}\n{} commits analyzed in {}s.",
+                table,
+                series.len(),
+                (elapsed * 10000.0).round() / 10000.0
+            )
+        }
+        _ => "No output format specified.".to_string(),
+    }
+}
+
+/// Renders the report in the given format. When a summary is given, it's printed below tables and
+/// added to the SARIF run properties. For JSON and CSV it changes the shape of the output, so the
+/// caller only passes it on request.
 pub fn generate_output(
     file_data_list: &Vec<FileData>,
     format: String,
     elapsed: &f64,
     output_limit: usize,
+    show_functions: bool,
+    config: &FtaConfigResolved,
+    summary: Option<&Summary>,
 ) -> String {
     let mut output = String::new();
 
     match Some(format.as_str()) {
         Some("json") => {
-            output = serde_json::to_string(file_data_list).unwrap();
+            output = json_envelope("files", file_data_list, summary);
+        }
+        Some("sarif") => {
+            output = generate_sarif(file_data_list, config, summary);
+        }
+        Some("html") => {
+            output = generate_html(file_data_list, config, summary);
+        }
+        Some("markdown") => {
+            output = generate_markdown(
+                file_data_list,
+                elapsed,
+                output_limit,
+                show_functions,
+                config,
+                summary,
+            );
         }
         Some("csv") => {
-            output.push_str("File,Num. lines,FTA Score (Lower is better),Assessment");
+            output.push_str(
+                "File,Num. lines,FTA Score (Lower is better),Assessment,Cognitive Complexity",
+            );
             for file_data in file_data_list {
                 output.push_str(&format!(
-                    "\n{},{},{:.2},{}",
-                    file_data.file_name,
+                    "\n{},{},{:.2},{},{}",
+                    csv_field(&file_data.file_name),
                     file_data.line_count,
                     file_data.fta_score,
-                    file_data.assessment
+                    csv_field(&file_data.assessment),
+                    file_data.cognitive
                 ));
             }
+            if let Some(summary) = summary {
+                output.push_str(&format!("\n\n{}", format_summary_csv(summary)));
+            }
         }
         Some("table") => {
             let mut table = Table::new();
@@ -44,25 +960,51 @@ pub fn generate_output(
             table.set_header(vec![
                 "File",
                 "Num. lines",
+                "Cognitive",
                 "FTA Score (Lower is better)",
                 "Assessment",
             ]);
 
-            for file_data in file_data_list {
-                if table.row_iter().count() >= output_limit {
-                    continue;
-                }
+            for file_data in file_data_list.iter().take(output_limit) {
                 table.add_row(vec![
                     truncate_string(&file_data.file_name, 50),
                     file_data.line_count.to_string(),
+                    file_data.cognitive.to_string(),
                     format!("{:.2}", file_data.fta_score),
-                    file_data.assessment.clone().to_string(),
+                    table_assessment(file_data.assessment.clone(), &file_data.suppression),
                 ]);
+
+                if show_functions {
+                    let bands = assessment_bands(&resolve_for_file(config, &file_data.file_name));
+                    let mut functions = file_data.functions.iter().collect::<Vec<_>>();
+                    functions.sort_by(|a, b| b.fta_score.partial_cmp(&a.fta_score).unwrap());
+
+                    for function in functions {
+                        let location = format!(":{}-{}", function.start_line, function.end_line);
+                        table.add_row(vec![
+                            format!(
+                                "  ↳ {}{}",
+                                truncate_string(&function.name, 44 - location.len()),
+                                location
+                            ),
+                            function.line_count.to_string(),
+                            function.cognitive.to_string(),
+                            format!("{:.2}", function.fta_score),
+                            table_assessment(
+                                get_assessment(function.fta_score, &bands).0,
+                                &function.suppression,
+                            ),
+                        ]);
+                    }
+                }
             }
 
             output = format!(
-                "{}\n{} files analyzed in {}s.",
+                "{}\n{}{} files analyzed in {}s.",
                 table.to_string(),
+                summary
+                    .map(|summary| format!("{}\n", format_summary_table(summary)))
+                    .unwrap_or_default(),
                 file_data_list.len(),
                 (elapsed * 10000.0).round() / 10000.0
             );
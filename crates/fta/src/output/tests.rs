@@ -1,13 +1,27 @@
 #[cfg(test)]
 mod tests {
-    use crate::output::{generate_output, truncate_string};
-    use crate::structs::{FileData, HalsteadMetrics};
+    use crate::config::get_default_config;
+    use crate::diff::{self, FileDiff, HalsteadDelta, MetricDelta, ReportDiff};
+    use crate::error::{ParseFailure, ScoreCapBreach};
+    use crate::group::GroupData;
+    use crate::history::{FileScore, RevisionScores};
+    use crate::hotspots::Hotspot;
+    use crate::output::{
+        format_failures_csv, generate_diff_output, generate_group_output, generate_history_output,
+        generate_hotspot_output, generate_output, generate_score_cap_summary, truncate_string,
+    };
+    use crate::structs::{
+        AssessmentBand, FileData, FunctionData, HalsteadMetrics, Severity, Suppression,
+    };
+    use crate::summary::{summarize, ScoreStatistics, Summary};
+    use serde_json::{json, Value};
 
     fn get_test_data() -> Vec<FileData> {
         vec![
             FileData {
                 file_name: "test.js".to_string(),
                 cyclo: 1,
+                cognitive: 0,
                 halstead: HalsteadMetrics {
                     uniq_operators: 1,
                     uniq_operands: 2,
@@ -24,10 +38,14 @@ mod tests {
                 line_count: 1,
                 fta_score: 45.00,
                 assessment: "OK".to_string(),
+                severity: Severity::None,
+                functions: vec![],
+                suppression: None,
             },
             FileData {
                 file_name: "foo.tsx".to_string(),
                 cyclo: 1,
+                cognitive: 3,
                 halstead: HalsteadMetrics {
                     uniq_operators: 1,
                     uniq_operands: 2,
@@ -44,10 +62,14 @@ mod tests {
                 line_count: 25,
                 fta_score: 95.00,
                 assessment: "OK".to_string(),
+                severity: Severity::None,
+                functions: vec![],
+                suppression: None,
             },
             FileData {
                 file_name: "bar.jsx".to_string(),
                 cyclo: 1,
+                cognitive: 8,
                 halstead: HalsteadMetrics {
                     uniq_operators: 1,
                     uniq_operands: 2,
@@ -64,6 +86,9 @@ mod tests {
                 line_count: 50,
                 fta_score: 145.00,
                 assessment: "OK".to_string(),
+                severity: Severity::None,
+                functions: vec![],
+                suppression: None,
             },
         ]
     }
@@ -84,6 +109,33 @@ mod tests {
         json.chars().filter(|&c| !c.is_whitespace()).collect()
     }
 
+    fn get_group_test_data() -> Vec<GroupData> {
+        vec![
+            GroupData {
+                group: "src/features".to_string(),
+                file_count: 3,
+                line_count: 120,
+                mean_score: 61.5,
+                median_score: 60.0,
+                p90_score: 75.25,
+                max_score: 75.25,
+                effort: 12345.678,
+                bugs: 1.5,
+            },
+            GroupData {
+                group: ".".to_string(),
+                file_count: 1,
+                line_count: 10,
+                mean_score: 20.0,
+                median_score: 20.0,
+                p90_score: 20.0,
+                max_score: 20.0,
+                effort: 100.0,
+                bugs: 0.25,
+            },
+        ]
+    }
+
     #[test]
     fn test_truncate_string() {
         assert_eq!(
@@ -95,6 +147,15 @@ mod tests {
         assert_eq!(truncate_string("abcdef", 5), "...ef");
         assert_eq!(truncate_string("abcdef", 4), "...f");
         assert_eq!(truncate_string("abcdef", 3), "...");
+        assert_eq!(truncate_string("abcdef", 2), "...");
+    }
+
+    #[test]
+    fn test_truncate_string_with_multibyte_characters() {
+        assert_eq!(truncate_string("größenÄnderung", 8), "...erung");
+        assert_eq!(truncate_string("größenÄnderung", 11), "...Änderung");
+        assert_eq!(truncate_string("计算复杂度分析器", 5), "...析器");
+        assert_eq!(truncate_string("计算复杂度分析器", 8), "计算复杂度分析器");
     }
 
     #[test]
@@ -102,31 +163,369 @@ mod tests {
         let file_data_list = get_test_data();
         let output_str = format!(
             "\n{}\n",
-            generate_output(&file_data_list, "csv".to_string(), &0.1_f64, 100)
+            generate_output(
+                &file_data_list,
+                "csv".to_string(),
+                &0.1_f64,
+                100,
+                false,
+                &get_default_config(),
+                None
+            )
         );
         let expected_output_raw = r##"
-            File,Num. lines,FTA Score (Lower is better),Assessment
-            test.js,1,45.00,OK
-            foo.tsx,25,95.00,OK
-            bar.jsx,50,145.00,OK
+            File,Num. lines,FTA Score (Lower is better),Assessment,Cognitive Complexity
+            test.js,1,45.00,OK,0
+            foo.tsx,25,95.00,OK,3
+            bar.jsx,50,145.00,OK,8
         "##;
         let expected_output = format_expected_output(expected_output_raw);
         assert_eq!(output_str, expected_output);
     }
 
+    #[test]
+    fn test_output_csv_format_quotes_fields() {
+        let mut file_data_list = get_test_data();
+        file_data_list[0].file_name = "src/a,b.js".to_string();
+        file_data_list[0].assessment = "Needs \"work\"".to_string();
+        let output = generate_output(
+            &file_data_list,
+            "csv".to_string(),
+            &0.1_f64,
+            100,
+            false,
+            &get_default_config(),
+            None,
+        );
+        assert_eq!(
+            output.lines().nth(1),
+            Some("\"src/a,b.js\",1,45.00,\"Needs \"\"work\"\"\",0")
+        );
+    }
+
+    #[test]
+    fn test_group_output_csv_format() {
+        let output_str = format!(
+            "\n{}\n",
+            generate_group_output(&get_group_test_data(), "csv", &0.1_f64, 100, 4, None)
+        );
+        let expected_output_raw = r##"
+            Group,Files,Num. lines,Mean FTA Score,Median FTA Score,P90 FTA Score,Max FTA Score,Halstead Effort,Halstead Bugs
+            src/features,3,120,61.50,60.00,75.25,75.25,12345.68,1.50
+            .,1,10,20.00,20.00,20.00,20.00,100.00,0.25
+        "##;
+        assert_eq!(output_str, format_expected_output(expected_output_raw));
+    }
+
+    #[test]
+    fn test_group_output_table_format() {
+        let output_str = format!(
+            "\n{}\n",
+            generate_group_output(&get_group_test_data(), "table", &0.1_f64, 1, 4, None)
+        );
+        let expected_output_raw = r##"
+            ┌──────────────┬───────┬────────────┬───────┬────────┬───────┬───────┬────────┬──────┐
+            │ Group        ┆ Files ┆ Num. lines ┆ Mean  ┆ Median ┆ P90   ┆ Max   ┆ Effort ┆ Bugs │
+            ╞══════════════╪═══════╪════════════╪═══════╪════════╪═══════╪═══════╪════════╪══════╡
+            │ src/features ┆ 3     ┆ 120        ┆ 61.50 ┆ 60.00  ┆ 75.25 ┆ 75.25 ┆ 12346  ┆ 1.50 │
+            └──────────────┴───────┴────────────┴───────┴────────┴───────┴───────┴────────┴──────┘
+            4 files analyzed in 0.1s, grouped into 2 groups.
+        "##;
+        assert_eq!(output_str, format_expected_output(expected_output_raw));
+    }
+
+    fn get_history_test_data() -> Vec<RevisionScores> {
+        let file_score = |file_name: &str, fta_score: f64| FileScore {
+            file_name: file_name.to_string(),
+            fta_score,
+        };
+        vec![
+            RevisionScores {
+                commit: "4f1c2a9e0b7d3c5e6f8a9b0c1d2e3f4a5b6c7d8e".to_string(),
+                date: "2024-01-02".to_string(),
+                file_count: 0,
+                line_count: 0,
+                scores: None,
+                files: vec![],
+            },
+            RevisionScores {
+                commit: "9a8b7c6d5e4f3a2b1c0d9e8f7a6b5c4d3e2f1a0b".to_string(),
+                date: "2024-02-01".to_string(),
+                file_count: 2,
+                line_count: 60,
+                scores: Some(ScoreStatistics {
+                    mean: 40.0,
+                    median: 40.0,
+                    p75: 60.0,
+                    p90: 60.0,
+                    p95: 60.0,
+                    max: 60.0,
+                }),
+                files: vec![
+                    file_score("src/a,b.ts", 20.0),
+                    file_score("src/cart.ts", 60.0),
+                ],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_history_output_csv_format() {
+        let output_str = format!(
+            "\n{}\n",
+            generate_history_output(&get_history_test_data(), "csv", &0.1_f64)
+        );
+        let expected_output_raw = r##"
+            Commit,Date,Files,Num. lines,Mean FTA Score,Median FTA Score,Max FTA Score,"src/a,b.ts",src/cart.ts
+            4f1c2a9e0b7d3c5e6f8a9b0c1d2e3f4a5b6c7d8e,2024-01-02,0,0,,,,,
+            9a8b7c6d5e4f3a2b1c0d9e8f7a6b5c4d3e2f1a0b,2024-02-01,2,60,40.00,40.00,60.00,20.00,60.00
+        "##;
+        assert_eq!(output_str, format_expected_output(expected_output_raw));
+    }
+
+    #[test]
+    fn test_history_output_table_format() {
+        let output_str = format!(
+            "\n{}\n",
+            generate_history_output(&get_history_test_data(), "table", &0.1_f64)
+        );
+        let expected_output_raw = r##"
+            ┌────────────┬────────────┬───────┬────────────┬───────┬────────┬───────┐
+            │ Commit     ┆ Date       ┆ Files ┆ Num. lines ┆ Mean  ┆ Median ┆ Max   │
+            ╞════════════╪════════════╪═══════╪════════════╪═══════╪════════╪═══════╡
+            │ 4f1c2a9e0b ┆ 2024-01-02 ┆ 0     ┆ 0          ┆       ┆        ┆       │
+            ├╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌┤
+            │ 9a8b7c6d5e ┆ 2024-02-01 ┆ 2     ┆ 60         ┆ 40.00 ┆ 40.00  ┆ 60.00 │
+            └────────────┴────────────┴───────┴────────────┴───────┴────────┴───────┘
+            2 commits analyzed in 0.1s.
+        "##;
+        assert_eq!(output_str, format_expected_output(expected_output_raw));
+
+        let series: Value = serde_json::from_str(&generate_history_output(
+            &get_history_test_data(),
+            "json",
+            &0.1_f64,
+        ))
+        .unwrap();
+        assert_eq!(series[0]["scores"], Value::Null);
+        assert_eq!(series[1]["scores"]["mean"], 40.0);
+        assert_eq!(
+            series[1]["files"][1],
+            json!({ "file_name": "src/cart.ts", "fta_score": 60.0 })
+        );
+    }
+
+    fn get_diff_test_data() -> ReportDiff {
+        let file_diff = |file_name: &str, old_score: f64, new_score: f64, cyclo: i64| FileDiff {
+            file_name: file_name.to_string(),
+            old_score,
+            new_score,
+            delta: MetricDelta {
+                fta_score: new_score - old_score,
+                cyclo,
+                cognitive: cyclo * 2,
+                line_count: cyclo * 10,
+                halstead: HalsteadDelta {
+                    uniq_operators: cyclo,
+                    uniq_operands: cyclo,
+                    total_operators: cyclo,
+                    total_operands: cyclo,
+                    program_length: cyclo,
+                    vocabulary_size: cyclo,
+                    volume: cyclo as f64 * 12.5,
+                    difficulty: 1.0,
+                    effort: 2.0,
+                    time: 3.0,
+                    bugs: cyclo as f64 * 0.01,
+                },
+            },
+        };
+        let file_score = |file_name: &str, fta_score: f64| diff::FileScore {
+            file_name: file_name.to_string(),
+            fta_score,
+        };
+        ReportDiff {
+            changed: vec![
+                file_diff("src/cart.ts", 40.0, 52.5, 3),
+                file_diff("src/a|b.ts", 60.0, 45.0, -2),
+            ],
+            added: vec![file_score("src/checkout.ts", 35.0)],
+            removed: vec![file_score("src/legacy.ts", 70.0)],
+            unchanged_count: 4,
+        }
+    }
+
+    #[test]
+    fn test_diff_output_table_format() {
+        let output = generate_diff_output(&get_diff_test_data(), "table");
+        let expected_output_raw = r##"
+            ┌─────────────┬────────────────┬─────────────┬─────────┬─────────────┬─────────┬──────────┬────────┐
+            │ File        ┆ FTA Score      ┆ Δ FTA Score ┆ Δ Cyclo ┆ Δ Cognitive ┆ Δ Lines ┆ Δ Volume ┆ Δ Bugs │
+            ╞═════════════╪════════════════╪═════════════╪═════════╪═════════════╪═════════╪══════════╪════════╡
+            │ src/cart.ts ┆ 40.00 -> 52.50 ┆ +12.50      ┆ +3      ┆ +6          ┆ +30     ┆ +37.50   ┆ +0.03  │
+            ├╌╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌┤
+            │ src/a|b.ts  ┆ 60.00 -> 45.00 ┆ -15.00      ┆ -2      ┆ -4          ┆ -20     ┆ -25.00   ┆ -0.02  │
+            └─────────────┴────────────────┴─────────────┴─────────┴─────────────┴─────────┴──────────┴────────┘
+            Added files:
+              src/checkout.ts: 35.00
+            Removed files:
+              src/legacy.ts: 70.00
+            2 changed, 1 added, 1 removed and 4 unchanged file(s).
+        "##;
+        // The added and removed files are indented below their headings
+        assert!(output.contains("\nAdded files:\n  src/checkout.ts: 35.00\n"));
+        assert_eq!(
+            format!("\n{}\n", format_expected_output(&output)),
+            format_expected_output(expected_output_raw)
+        );
+    }
+
+    #[test]
+    fn test_diff_output_markdown_format() {
+        let output_str = format!(
+            "\n{}\n",
+            generate_diff_output(&get_diff_test_data(), "markdown")
+        );
+        let expected_output_raw = r##"
+            | File | FTA Score | Δ FTA Score | Δ Cyclo | Δ Cognitive | Δ Lines | Δ Volume | Δ Bugs |
+            | --- | ---: | ---: | ---: | ---: | ---: | ---: | ---: |
+            | `src/cart.ts` | 40.00 -> 52.50 | +12.50 | +3 | +6 | +30 | +37.50 | +0.03 |
+            | `src/a\|b.ts` | 60.00 -> 45.00 | -15.00 | -2 | -4 | -20 | -25.00 | -0.02 |
+
+            **Added files**
+
+            - `src/checkout.ts` (35.00)
+
+            **Removed files**
+
+            - `src/legacy.ts` (70.00)
+
+            2 changed, 1 added, 1 removed and 4 unchanged file(s).
+        "##;
+        assert_eq!(output_str, format_expected_output(expected_output_raw));
+    }
+
+    #[test]
+    fn test_diff_output_json_format() {
+        let diff: Value =
+            serde_json::from_str(&generate_diff_output(&get_diff_test_data(), "json")).unwrap();
+        assert_eq!(diff["changed"][0]["delta"]["fta_score"], 12.5);
+        assert_eq!(diff["changed"][1]["delta"]["halstead"]["volume"], -25.0);
+        assert_eq!(
+            diff["added"],
+            json!([{ "file_name": "src/checkout.ts", "fta_score": 35.0 }])
+        );
+        assert_eq!(diff["unchanged_count"], 4);
+    }
+
+    fn get_hotspot_test_data() -> Vec<Hotspot> {
+        vec![
+            Hotspot {
+                file_name: "src/cart.ts".to_string(),
+                fta_score: 52.5,
+                assessment: "Could be better".to_string(),
+                commits: 20,
+                authors: 3,
+                last_modified: "2024-05-01".to_string(),
+                hotspot_score: 81.25,
+            },
+            Hotspot {
+                file_name: "src/constants.ts".to_string(),
+                fta_score: 10.0,
+                assessment: "OK".to_string(),
+                commits: 4,
+                authors: 1,
+                last_modified: "2024-01-15".to_string(),
+                hotspot_score: 12.5,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_hotspot_output_table_format() {
+        let output_str = format!(
+            "\n{}\n",
+            generate_hotspot_output(
+                &get_hotspot_test_data(),
+                "table",
+                &0.1_f64,
+                1,
+                5,
+                "1 year ago"
+            )
+        );
+        let expected_output_raw = r##"
+            ┌─────────────┬───────────────┬───────────┬─────────┬─────────┬───────────────┬─────────────────┐
+            │ File        ┆ Hotspot score ┆ FTA Score ┆ Commits ┆ Authors ┆ Last modified ┆ Assessment      │
+            ╞═════════════╪═══════════════╪═══════════╪═════════╪═════════╪═══════════════╪═════════════════╡
+            │ src/cart.ts ┆ 81.25         ┆ 52.50     ┆ 20      ┆ 3       ┆ 2024-05-01    ┆ Could be better │
+            └─────────────┴───────────────┴───────────┴─────────┴─────────┴───────────────┴─────────────────┘
+            5 files analyzed in 0.1s, 2 changed since 1 year ago.
+        "##;
+        assert_eq!(output_str, format_expected_output(expected_output_raw));
+    }
+
+    #[test]
+    fn test_hotspot_output_json_format() {
+        let output_str = generate_hotspot_output(
+            &get_hotspot_test_data(),
+            "json",
+            &0.1_f64,
+            1,
+            5,
+            "1 year ago",
+        );
+        let hotspots: Value = serde_json::from_str(&output_str).unwrap();
+
+        // JSON output isn't limited
+        assert_eq!(hotspots.as_array().unwrap().len(), 2);
+        assert_eq!(
+            hotspots[0],
+            json!({
+                "file_name": "src/cart.ts",
+                "fta_score": 52.5,
+                "assessment": "Could be better",
+                "commits": 20,
+                "authors": 3,
+                "last_modified": "2024-05-01",
+                "hotspot_score": 81.25
+            })
+        );
+    }
+
+    #[test]
+    fn test_group_output_json_format() {
+        let output_str =
+            generate_group_output(&get_group_test_data(), "json", &0.1_f64, 1, 4, None);
+        let groups: Value = serde_json::from_str(&output_str).unwrap();
+
+        assert_eq!(groups.as_array().unwrap().len(), 2);
+        assert_eq!(groups[0]["group"], "src/features");
+        assert_eq!(groups[0]["p90_score"], 75.25);
+        assert_eq!(groups[1]["bugs"], 0.25);
+    }
+
     #[test]
     fn test_output_csv_format_is_not_limited_by_output_limit() {
         let file_data_list = get_test_data();
         let output_limit = 1;
         let output_str = format!(
             "\n{}\n",
-            generate_output(&file_data_list, "csv".to_string(), &0.1_f64, output_limit)
+            generate_output(
+                &file_data_list,
+                "csv".to_string(),
+                &0.1_f64,
+                output_limit,
+                false,
+                &get_default_config(),
+                None
+            )
         );
         let expected_output_raw = r##"
-            File,Num. lines,FTA Score (Lower is better),Assessment
-            test.js,1,45.00,OK
-            foo.tsx,25,95.00,OK
-            bar.jsx,50,145.00,OK
+            File,Num. lines,FTA Score (Lower is better),Assessment,Cognitive Complexity
+            test.js,1,45.00,OK,0
+            foo.tsx,25,95.00,OK,3
+            bar.jsx,50,145.00,OK,8
         "##;
         let expected_output = format_expected_output(expected_output_raw);
         assert_eq!(output_str, expected_output);
@@ -135,17 +534,25 @@ mod tests {
     #[test]
     fn test_output_table_format() {
         let file_data_list = get_test_data();
-        let output_str = generate_output(&file_data_list, "table".to_string(), &0.1_f64, 100);
+        let output_str = generate_output(
+            &file_data_list,
+            "table".to_string(),
+            &0.1_f64,
+            100,
+            false,
+            &get_default_config(),
+            None,
+        );
         let expected_output_raw = r##"
-            ┌─────────┬────────────┬─────────────────────────────┬────────────┐
-            │ File    ┆ Num. lines ┆ FTA Score (Lower is better) ┆ Assessment │
-            ╞═════════╪════════════╪═════════════════════════════╪════════════╡
-            │ test.js ┆ 1          ┆ 45.00                       ┆ OK         │
-            ├╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌┤
-            │ foo.tsx ┆ 25         ┆ 95.00                       ┆ OK         │
-            ├╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌┤
-            │ bar.jsx ┆ 50         ┆ 145.00                      ┆ OK         │
-            └─────────┴────────────┴─────────────────────────────┴────────────┘
+            ┌─────────┬────────────┬───────────┬─────────────────────────────┬────────────┐
+            │ File    ┆ Num. lines ┆ Cognitive ┆ FTA Score (Lower is better) ┆ Assessment │
+            ╞═════════╪════════════╪═══════════╪═════════════════════════════╪════════════╡
+            │ test.js ┆ 1          ┆ 0         ┆ 45.00                       ┆ OK         │
+            ├╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌┤
+            │ foo.tsx ┆ 25         ┆ 3         ┆ 95.00                       ┆ OK         │
+            ├╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌┤
+            │ bar.jsx ┆ 50         ┆ 8         ┆ 145.00                      ┆ OK         │
+            └─────────┴────────────┴───────────┴─────────────────────────────┴────────────┘
             3 files analyzed in 0.1s.
         "##;
 
@@ -160,14 +567,21 @@ mod tests {
     fn test_output_table_can_be_limited() {
         let file_data_list = get_test_data();
         let output_limit = 1;
-        let output_str =
-            generate_output(&file_data_list, "table".to_string(), &0.1_f64, output_limit);
+        let output_str = generate_output(
+            &file_data_list,
+            "table".to_string(),
+            &0.1_f64,
+            output_limit,
+            false,
+            &get_default_config(),
+            None,
+        );
         let expected_output_raw = r##"
-            ┌─────────┬────────────┬─────────────────────────────┬────────────┐
-            │ File    ┆ Num. lines ┆ FTA Score (Lower is better) ┆ Assessment │
-            ╞═════════╪════════════╪═════════════════════════════╪════════════╡
-            │ test.js ┆ 1          ┆ 45.00                       ┆ OK         │
-            └─────────┴────────────┴─────────────────────────────┴────────────┘
+            ┌─────────┬────────────┬───────────┬─────────────────────────────┬────────────┐
+            │ File    ┆ Num. lines ┆ Cognitive ┆ FTA Score (Lower is better) ┆ Assessment │
+            ╞═════════╪════════════╪═══════════╪═════════════════════════════╪════════════╡
+            │ test.js ┆ 1          ┆ 0         ┆ 45.00                       ┆ OK         │
+            └─────────┴────────────┴───────────┴─────────────────────────────┴────────────┘
             3 files analyzed in 0.1s.
         "##;
 
@@ -178,23 +592,297 @@ mod tests {
         assert_eq!(output_str, expected_output);
     }
 
+    #[test]
+    fn test_output_table_can_show_functions() {
+        let mut file_data_list = get_test_data();
+        file_data_list.truncate(1);
+        file_data_list[0].functions = vec![
+            FunctionData {
+                name: "simple".to_string(),
+                start_line: 1,
+                end_line: 3,
+                cyclo: 1,
+                cognitive: 0,
+                halstead: HalsteadMetrics {
+                    uniq_operators: 1,
+                    uniq_operands: 2,
+                    total_operators: 3,
+                    total_operands: 4,
+                    program_length: 5,
+                    vocabulary_size: 6,
+                    volume: 7.0,
+                    difficulty: 8.0,
+                    effort: 9.0,
+                    time: 10.0,
+                    bugs: 11.0,
+                },
+                line_count: 3,
+                fta_score: 12.5,
+                suppression: None,
+            },
+            FunctionData {
+                name: "Parser.parse".to_string(),
+                start_line: 5,
+                end_line: 40,
+                cyclo: 12,
+                cognitive: 9,
+                halstead: HalsteadMetrics {
+                    uniq_operators: 1,
+                    uniq_operands: 2,
+                    total_operators: 3,
+                    total_operands: 4,
+                    program_length: 5,
+                    vocabulary_size: 6,
+                    volume: 7.0,
+                    difficulty: 8.0,
+                    effort: 9.0,
+                    time: 10.0,
+                    bugs: 11.0,
+                },
+                line_count: 36,
+                fta_score: 55.0,
+                suppression: None,
+            },
+        ];
+        let output_str = generate_output(
+            &file_data_list,
+            "table".to_string(),
+            &0.1_f64,
+            100,
+            true,
+            &get_default_config(),
+            None,
+        );
+        let expected_output_raw = r##"
+            ┌───────────────────────┬────────────┬───────────┬───────────┬─────────────────┐
+            │ File                  ┆ Num. lines ┆ Cognitive ┆ FTA Score ┆ Assessment      │
+            │                       ┆            ┆           ┆ (Lower is ┆                 │
+            │                       ┆            ┆           ┆ better)   ┆                 │
+            ╞═══════════════════════╪════════════╪═══════════╪═══════════╪═════════════════╡
+            │ test.js               ┆ 1          ┆ 0         ┆ 45.00     ┆ OK              │
+            ├╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌┤
+            │   ↳ Parser.parse:5-40 ┆ 36         ┆ 9         ┆ 55.00     ┆ Could be better │
+            ├╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌┼╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌┤
+            │   ↳ simple:1-3        ┆ 3          ┆ 0         ┆ 12.50     ┆ OK              │
+            └───────────────────────┴────────────┴───────────┴───────────┴─────────────────┘
+            1 files analyzed in 0.1s.
+        "##;
+
+        let expected_output = format_expected_output(expected_output_raw);
+        let expected_output = expected_output
+            .trim_start_matches('\n')
+            .trim_end_matches('\n');
+        assert_eq!(output_str, expected_output);
+    }
+
     #[test]
     fn test_output_unspecified_format() {
         let file_data_list = get_test_data();
-        let output_str = generate_output(&file_data_list, "unspecified".to_string(), &0.1_f64, 100);
+        let output_str = generate_output(
+            &file_data_list,
+            "unspecified".to_string(),
+            &0.1_f64,
+            100,
+            false,
+            &get_default_config(),
+            None,
+        );
         let expected_output = "No output format specified.";
         assert_eq!(output_str, expected_output);
     }
 
+    #[test]
+    fn test_output_sarif_format() {
+        let mut file_data_list = get_test_data();
+        file_data_list[1].assessment = "Could be better".to_string();
+        file_data_list[2].assessment = "Needs improvement".to_string();
+        file_data_list[1].severity = Severity::Warning;
+        file_data_list[2].severity = Severity::Error;
+        file_data_list[2].file_name = "src\\components\\bar baz.jsx".to_string();
+        let mut config = get_default_config();
+        config.score_cap = 100;
+        let output_str = generate_output(
+            &file_data_list,
+            "sarif".to_string(),
+            &0.1_f64,
+            100,
+            false,
+            &config,
+            None,
+        );
+        let sarif: Value = serde_json::from_str(&output_str).unwrap();
+
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "fta");
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "fta/score-cap");
+        assert_eq!(run["tool"]["driver"]["rules"][1]["id"], "fta/assessment");
+
+        let results = run["results"].as_array().unwrap();
+        let summary: Vec<(&str, &str, &str)> = results
+            .iter()
+            .map(|result| {
+                (
+                    result["ruleId"].as_str().unwrap(),
+                    result["level"].as_str().unwrap(),
+                    result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"]
+                        .as_str()
+                        .unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("fta/assessment", "warning", "foo.tsx"),
+                ("fta/score-cap", "error", "src/components/bar%20baz.jsx"),
+                ("fta/assessment", "error", "src/components/bar%20baz.jsx"),
+            ]
+        );
+        assert_eq!(results[1]["ruleIndex"], 0);
+        assert_eq!(results[1]["properties"]["fta_score"], 145.0);
+    }
+
+    fn get_suppressed_test_data() -> Vec<FileData> {
+        let mut file_data_list = get_test_data();
+        file_data_list[1].suppression = Some(Suppression::ScoreCap {
+            line: 1,
+            score_cap: 100,
+            reason: Some("state machine".to_string()),
+        });
+        file_data_list[2].assessment = "Needs improvement".to_string();
+        file_data_list[2].severity = Severity::Error;
+        file_data_list[2].suppression = Some(Suppression::IgnoreFile {
+            line: 3,
+            reason: None,
+        });
+        file_data_list
+    }
+
+    #[test]
+    fn test_output_sarif_format_with_suppressions() {
+        let mut config = get_default_config();
+        config.score_cap = 50;
+        let output_str = generate_output(
+            &get_suppressed_test_data(),
+            "sarif".to_string(),
+            &0.1_f64,
+            100,
+            false,
+            &config,
+            None,
+        );
+        let sarif: Value = serde_json::from_str(&output_str).unwrap();
+
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        let summary: Vec<(&str, &str, &str)> = results
+            .iter()
+            .map(|result| {
+                (
+                    result["ruleId"].as_str().unwrap(),
+                    result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"]
+                        .as_str()
+                        .unwrap(),
+                    result["suppressions"][0]["justification"]
+                        .as_str()
+                        .unwrap_or_default(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "fta/score-cap",
+                    "foo.tsx",
+                    "fta-score-cap: 100 (state machine)"
+                ),
+                ("fta/score-cap", "bar.jsx", "fta-ignore-file"),
+                ("fta/assessment", "bar.jsx", "fta-ignore-file"),
+            ]
+        );
+        assert_eq!(results[0]["suppressions"][0]["kind"], "inSource");
+    }
+
+    #[test]
+    fn test_output_table_with_suppressions() {
+        let file_data_list = get_suppressed_test_data();
+        let summary = summarize(&file_data_list, &[], 0.1, &get_default_config());
+        let output_str = generate_output(
+            &file_data_list,
+            "table".to_string(),
+            &0.1_f64,
+            100,
+            false,
+            &get_default_config(),
+            Some(&summary),
+        );
+
+        assert!(output_str.contains("┆ OK (score cap 100)"));
+        assert!(output_str.contains("(ignored)"));
+        assert!(output_str.contains(
+            "  Suppressed:\n    bar.jsx:3: fta-ignore-file\n    foo.tsx:1: fta-score-cap: 100 (state machine)\n"
+        ));
+    }
+
+    #[test]
+    fn test_output_sarif_format_with_custom_bands() {
+        let mut file_data_list = get_test_data();
+        file_data_list[0].assessment = "Watch".to_string();
+        file_data_list[0].severity = Severity::Note;
+        let mut config = get_default_config();
+        config.assessment_bands = Some(vec![
+            AssessmentBand {
+                label: "Fine".to_string(),
+                above: None,
+                severity: Severity::None,
+            },
+            AssessmentBand {
+                label: "Watch".to_string(),
+                above: Some(40.0),
+                severity: Severity::Note,
+            },
+        ]);
+        let output_str = generate_output(
+            &file_data_list,
+            "sarif".to_string(),
+            &0.1_f64,
+            100,
+            false,
+            &config,
+            None,
+        );
+        let sarif: Value = serde_json::from_str(&output_str).unwrap();
+
+        let run = &sarif["runs"][0];
+        assert_eq!(
+            run["tool"]["driver"]["rules"][1]["fullDescription"]["text"],
+            "The FTA score of the file is in one of the following assessment bands: \"Watch\" (note)."
+        );
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["level"], "note");
+        assert_eq!(results[0]["ruleId"], "fta/assessment");
+    }
+
     #[test]
     fn test_output_json_format() {
         let file_data_list = get_test_data();
-        let output_str = generate_output(&file_data_list, "json".to_string(), &0.1_f64, 100);
+        let output_str = generate_output(
+            &file_data_list,
+            "json".to_string(),
+            &0.1_f64,
+            100,
+            false,
+            &get_default_config(),
+            None,
+        );
 
         let expected_output = r##"[
             {
                 "file_name": "test.js",
                 "cyclo": 1,
+                "cognitive": 0,
                 "halstead":
                 {
                     "uniq_operators": 1,
@@ -211,11 +899,14 @@ mod tests {
                 },
                 "line_count": 1,
                 "fta_score": 45.0,
-                "assessment": "OK"
+                "assessment": "OK",
+                "severity": "none",
+                "functions": []
             },
             {
                 "file_name": "foo.tsx",
                 "cyclo": 1,
+                "cognitive": 3,
                 "halstead":
                 {
                     "uniq_operators": 1,
@@ -232,11 +923,14 @@ mod tests {
                 },
                 "line_count": 25,
                 "fta_score": 95.0,
-                "assessment": "OK"
+                "assessment": "OK",
+                "severity": "none",
+                "functions": []
             },
             {
                 "file_name": "bar.jsx",
                 "cyclo": 1,
+                "cognitive": 8,
                 "halstead":
                 {
                     "uniq_operators": 1,
@@ -253,7 +947,9 @@ mod tests {
                 },
                 "line_count": 50,
                 "fta_score": 145.0,
-                "assessment": "OK"
+                "assessment": "OK",
+                "severity": "none",
+                "functions": []
             }
         ]"##;
 
@@ -262,4 +958,299 @@ mod tests {
             format_json_output(expected_output)
         );
     }
+
+    fn failure() -> ParseFailure {
+        ParseFailure {
+            file_name: "broken.ts".to_string(),
+            message: "Expected ',', got ';'".to_string(),
+            line: 4,
+            column: 12,
+        }
+    }
+
+    fn get_test_summary(file_data_list: &[FileData]) -> Summary {
+        summarize(file_data_list, &[failure()], 0.1, &get_default_config())
+    }
+
+    #[test]
+    fn test_output_table_with_summary() {
+        let file_data_list = get_test_data();
+        let summary = get_test_summary(&file_data_list);
+        let output_str = generate_output(
+            &file_data_list,
+            "table".to_string(),
+            &0.1_f64,
+            100,
+            false,
+            &get_default_config(),
+            Some(&summary),
+        );
+        let summary_block = output_str
+            .lines()
+            .skip_while(|line| *line != "Summary:")
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            summary_block,
+            vec![
+                "Summary:",
+                "  Files: 3 (1 could not be parsed)",
+                "  Lines: 76",
+                "  FTA Score: mean 95.00, median 95.00, p75 145.00, p90 145.00, p95 145.00, max 145.00",
+                "  Assessments: OK 3, Could be better 0, Needs improvement 0",
+                "  Distribution:",
+                "       0-10   0",
+                "      10-20   0",
+                "      20-30   0",
+                "      30-40   0",
+                "      40-50  ██████████████████████████████ 1",
+                "      50-60   0",
+                "      60-70   0",
+                "      70-80   0",
+                "      80-90   0",
+                "     90-100  ██████████████████████████████ 1",
+                "       100+  ██████████████████████████████ 1",
+                "  Failed to parse:",
+                "    broken.ts:4:12: Expected ',', got ';'",
+                "3 files analyzed in 0.1s.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_output_markdown_format() {
+        let mut file_data_list = get_test_data();
+        file_data_list[1].assessment = "Needs improvement".to_string();
+        file_data_list[1].severity = Severity::Error;
+        let summary = get_test_summary(&file_data_list);
+        let output_str = format!(
+            "\n{}\n",
+            generate_output(
+                &file_data_list,
+                "markdown".to_string(),
+                &0.1_f64,
+                2,
+                false,
+                &get_default_config(),
+                Some(&summary),
+            )
+        );
+        let expected_output_raw = r##"
+            ## FTA report
+
+            **3 files**, 76 lines, analyzed in 0.1s.
+
+            | Mean | Median | P75 | P90 | P95 | Max |
+            | ---: | ---: | ---: | ---: | ---: | ---: |
+            | 95.00 | 95.00 | 145.00 | 145.00 | 145.00 | 145.00 |
+
+            🟢 OK: 2 · 🟡 Could be better: 0 · 🔴 Needs improvement: 1
+
+            ⚠️ 1 file(s) could not be parsed:
+
+            - `broken.ts:4:12`: Expected ',', got ';'
+
+            | File | Num. lines | Cognitive | FTA Score | Assessment |
+            | --- | ---: | ---: | ---: | --- |
+            | `test.js` | 1 | 0 | 45.00 | 🟢 OK |
+            | `foo.tsx` | 25 | 3 | 95.00 | 🔴 Needs improvement |
+
+            <details>
+            <summary>1 more file(s)</summary>
+
+            | File | Num. lines | Cognitive | FTA Score | Assessment |
+            | --- | ---: | ---: | ---: | --- |
+            | `bar.jsx` | 50 | 8 | 145.00 | 🟢 OK |
+
+            </details>
+        "##;
+        assert_eq!(output_str, format_expected_output(expected_output_raw));
+    }
+
+    #[test]
+    fn test_output_html_format() {
+        let mut file_data_list = get_test_data();
+        file_data_list[0].file_name = "src/</script><script>alert(1)</script>.js".to_string();
+        let summary = get_test_summary(&file_data_list);
+        let output_str = generate_output(
+            &file_data_list,
+            "html".to_string(),
+            &0.1_f64,
+            1,
+            false,
+            &get_default_config(),
+            Some(&summary),
+        );
+
+        assert!(output_str.starts_with("<!DOCTYPE html>"));
+        assert!(!output_str.contains("http://") && !output_str.contains("https://"));
+        // File names can't close the script element that holds the data
+        assert_eq!(output_str.matches("</script>").count(), 2);
+
+        let data = output_str
+            .split("<script id=\"fta-data\" type=\"application/json\">")
+            .nth(1)
+            .and_then(|rest| rest.split("</script>").next())
+            .unwrap();
+        let data: Value = serde_json::from_str(data).unwrap();
+        // Every file is included, regardless of the output limit
+        assert_eq!(data["files"].as_array().unwrap().len(), 3);
+        assert_eq!(data["files"][0]["file_name"], file_data_list[0].file_name);
+        assert_eq!(data["summary"]["file_count"], 3);
+        assert_eq!(data["score_scale"], 60.0);
+        assert_eq!(data["directories"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_output_json_with_summary() {
+        let file_data_list = get_test_data();
+        let summary = get_test_summary(&file_data_list);
+        let output_str = generate_output(
+            &file_data_list,
+            "json".to_string(),
+            &0.1_f64,
+            100,
+            false,
+            &get_default_config(),
+            Some(&summary),
+        );
+
+        assert!(output_str.starts_with(r#"{"version":"#));
+        let output: Value = serde_json::from_str(&output_str).unwrap();
+        assert_eq!(output["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(output["files"].as_array().unwrap().len(), 3);
+        assert_eq!(output["files"][0]["file_name"], "test.js");
+        assert_eq!(output["summary"]["file_count"], 3);
+        assert_eq!(output["summary"]["failures"][0]["file_name"], "broken.ts");
+        assert_eq!(output["summary"]["failures"][0]["line"], 4);
+        assert_eq!(output["summary"]["failures"][0]["column"], 12);
+        assert_eq!(output["summary"]["scores"]["median"], 95.0);
+        assert_eq!(output["summary"]["histogram"][10]["min"], 100.0);
+        assert_eq!(output["summary"]["histogram"][10]["max"], Value::Null);
+        assert_eq!(output["summary"]["assessments"][0]["assessment"], "OK");
+        assert_eq!(output["summary"]["assessments"][0]["count"], 3);
+    }
+
+    #[test]
+    fn test_output_csv_with_summary() {
+        let file_data_list = get_test_data();
+        let summary = get_test_summary(&file_data_list);
+        let output_str = generate_output(
+            &file_data_list,
+            "csv".to_string(),
+            &0.1_f64,
+            100,
+            false,
+            &get_default_config(),
+            Some(&summary),
+        );
+        let (files, summary) = output_str.split_once("\n\n").unwrap();
+
+        assert_eq!(files.lines().count(), 4);
+        assert_eq!(
+            summary.lines().take(12).collect::<Vec<_>>(),
+            vec![
+                "Summary,Value",
+                "Files,3",
+                "Num. lines,76",
+                "Parse failures,1",
+                "Elapsed (s),0.1",
+                "Mean FTA Score,95.00",
+                "Median FTA Score,95.00",
+                "P75 FTA Score,145.00",
+                "P90 FTA Score,145.00",
+                "P95 FTA Score,145.00",
+                "Max FTA Score,145.00",
+                "FTA Score 0-10,0",
+            ]
+        );
+        assert!(summary.ends_with(
+            "OK,3\nCould be better,0\nNeeds improvement,0\n\nFailed file,Line,Column,Message\nbroken.ts,4,12,\"Expected ',', got ';'\""
+        ));
+    }
+
+    #[test]
+    fn test_output_csv_failures_without_summary() {
+        let file_data_list = get_test_data();
+        let output_str = generate_output(
+            &file_data_list,
+            "csv".to_string(),
+            &0.1_f64,
+            100,
+            false,
+            &get_default_config(),
+            None,
+        );
+        assert!(!output_str.contains("Failed file"));
+
+        // The failures follow the report on their own, as they do in the summary
+        assert_eq!(
+            format_failures_csv(&[failure()]),
+            "Failed file,Line,Column,Message\nbroken.ts,4,12,\"Expected ',', got ';'\""
+        );
+        assert_eq!(format_failures_csv(&[]), "Failed file,Line,Column,Message");
+    }
+
+    #[test]
+    fn test_output_sarif_with_summary() {
+        let file_data_list = get_test_data();
+        let summary = get_test_summary(&file_data_list);
+        let output_str = generate_output(
+            &file_data_list,
+            "sarif".to_string(),
+            &0.1_f64,
+            100,
+            false,
+            &get_default_config(),
+            Some(&summary),
+        );
+        let sarif: Value = serde_json::from_str(&output_str).unwrap();
+
+        assert_eq!(sarif["runs"][0]["properties"]["summary"]["file_count"], 3);
+        let notification = &sarif["runs"][0]["invocations"][0]["toolExecutionNotifications"][0];
+        assert_eq!(notification["level"], "error");
+        assert_eq!(
+            notification["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "broken.ts"
+        );
+        assert_eq!(
+            notification["locations"][0]["physicalLocation"]["region"]["startLine"],
+            4
+        );
+    }
+
+    #[test]
+    fn test_score_cap_summary() {
+        let breaches = vec![
+            ScoreCapBreach {
+                file_name: "bar.jsx".to_string(),
+                fta_score: 145.0,
+                score_cap: 90,
+            },
+            ScoreCapBreach {
+                file_name: "foo.tsx".to_string(),
+                fta_score: 95.5,
+                score_cap: 90,
+            },
+        ];
+
+        assert_eq!(
+            generate_score_cap_summary(&breaches, 90, "table"),
+            "ScoreCapError! 2 file(s) are beyond the score cap of 90:\n  bar.jsx: 145.00\n  foo.tsx: 95.50"
+        );
+        let summary: Value =
+            serde_json::from_str(&generate_score_cap_summary(&breaches, 90, "json")).unwrap();
+        let expected: Value = serde_json::from_str(
+            r#"{
+                "score_cap": 90,
+                "breach_count": 2,
+                "breaches": [
+                    { "file_name": "bar.jsx", "fta_score": 145.0, "score_cap": 90 },
+                    { "file_name": "foo.tsx", "fta_score": 95.5, "score_cap": 90 }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(summary, expected);
+    }
 }
@@ -1,5 +1,6 @@
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 
+use log::warn;
 use swc_common::comments::Comment;
 use swc_common::sync::Lrc;
 use swc_common::{comments::Comments, BytePos, FileName, SourceMap};
@@ -8,16 +9,80 @@ use swc_ecma_parser::{error::Error, lexer::Lexer, Parser, StringInput, Syntax, T
 
 mod tests;
 
+/// An inline comment that changes how a file or function is checked.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DirectiveKind {
+    /// `fta-ignore-file`
+    IgnoreFile,
+    /// `fta-ignore-next-function`
+    IgnoreNextFunction,
+    /// `fta-score-cap: <score cap>`
+    ScoreCap(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Directive {
+    pub kind: DirectiveKind,
+    /// The explanation following `--`, if any.
+    pub reason: Option<String>,
+    /// The start of the comment within the parsed code, see `LineMap`.
+    pub pos: BytePos,
+}
+
+/// Reads a directive from the text of a comment, such as `fta-score-cap: 80 -- state machine`.
+/// The directive has to make up the whole comment, apart from the reason.
+pub fn parse_directive(text: &str) -> Option<(DirectiveKind, Option<String>)> {
+    let text = text.trim().trim_start_matches('*').trim();
+    let (directive, reason) = match text.split_once("--") {
+        Some((directive, reason)) => (directive.trim(), Some(reason.trim().to_string())),
+        None => (text, None),
+    };
+    let reason = reason.filter(|reason| !reason.is_empty());
+
+    let kind = match directive {
+        "fta-ignore-file" => DirectiveKind::IgnoreFile,
+        "fta-ignore-next-function" => DirectiveKind::IgnoreNextFunction,
+        _ => {
+            let score_cap = directive
+                .strip_prefix("fta-score-cap")?
+                .trim_start()
+                .strip_prefix(':')?
+                .trim();
+            match score_cap.parse() {
+                Ok(score_cap) => DirectiveKind::ScoreCap(score_cap),
+                Err(_) => {
+                    warn!(
+                        "Ignoring `{}`, as the score cap must be a positive whole number",
+                        directive
+                    );
+                    return None;
+                }
+            }
+        }
+    };
+
+    Some((kind, reason))
+}
+
 pub fn parse_module(
     source: &str,
     use_tsx: bool,
     include_comments: bool,
 ) -> (Result<Module, Error>, usize) {
+    let (parsed, line_count, _) = parse_module_with_directives(source, use_tsx, include_comments);
+    (parsed, line_count)
+}
+
+/// Like `parse_module`, but also returns the directives found in the comments of the module.
+pub fn parse_module_with_directives(
+    source: &str,
+    use_tsx: bool,
+    include_comments: bool,
+) -> (Result<Module, Error>, usize, Vec<Directive>) {
     let cm: Lrc<SourceMap> = Default::default();
-    let comments = CountingComments::new();
-    let code: String = source
-        .lines()
-        .filter(|line| !line.trim().is_empty()) // Remove lines that are empty or contain only whitespace
+    let comments = CollectingComments::new();
+    let code: String = non_empty_lines(source)
+        .map(|(_, line)| line)
         .collect::<Vec<_>>()
         .join("\n");
 
@@ -49,30 +114,106 @@ pub fn parse_module(
         line_count -= comments.count()
     };
 
-    (parsed, line_count)
+    (parsed, line_count, comments.directives())
 }
 
-struct CountingComments {
+/// Yields the (zero-based) original line index and content of every line that is parsed.
+/// Lines that are empty or contain only whitespace are removed before parsing.
+fn non_empty_lines(source: &str) -> impl Iterator<Item = (usize, &str)> {
+    source
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+}
+
+/// Maps positions in a module produced by `parse_module` back to lines in the original source.
+///
+/// `parse_module` strips empty lines before parsing, so spans refer to the condensed code rather
+/// than the file on disk. This keeps track of both so that callers can report real line numbers
+/// while still counting lines the same way the file-level `line_count` does.
+pub struct LineMap {
+    line_starts: Vec<usize>,
+    original_lines: Vec<usize>,
+}
+
+impl LineMap {
+    pub fn new(source: &str) -> LineMap {
+        let mut line_starts = Vec::new();
+        let mut original_lines = Vec::new();
+        let mut offset = 0;
+
+        for (index, line) in non_empty_lines(source) {
+            line_starts.push(offset);
+            original_lines.push(index + 1);
+            offset += line.len() + 1;
+        }
+
+        LineMap {
+            line_starts,
+            original_lines,
+        }
+    }
+
+    /// The zero-based line index within the parsed (condensed) code.
+    pub fn code_line(&self, pos: BytePos) -> usize {
+        // Every module is parsed into a fresh `SourceMap`, whose first file starts at `BytePos(1)`
+        let offset = pos.0.saturating_sub(1) as usize;
+        match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line.saturating_sub(1),
+        }
+    }
+
+    /// The one-based line number within the original source.
+    pub fn original_line(&self, pos: BytePos) -> usize {
+        self.original_lines
+            .get(self.code_line(pos))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The one-based column (in bytes) within the line. Lines are parsed as they are, so this is
+    /// the same in the original source.
+    pub fn column(&self, pos: BytePos) -> usize {
+        let offset = pos.0.saturating_sub(1) as usize;
+        let line_start = self
+            .line_starts
+            .get(self.code_line(pos))
+            .copied()
+            .unwrap_or_default();
+        offset.saturating_sub(line_start) + 1
+    }
+}
+
+/// Counts the lines taken up by comments, and collects the directives among them.
+struct CollectingComments {
     count: Cell<usize>,
+    directives: RefCell<Vec<Directive>>,
 }
 
-impl Comments for CountingComments {
-    fn add_leading(self: &CountingComments, _pos: BytePos, _comment: Comment) {
+impl Comments for CollectingComments {
+    fn add_leading(self: &CollectingComments, _pos: BytePos, _comment: Comment) {
         self.count
             .set(self.count.get() + 1 + _comment.text.matches('\n').count());
+        self.collect_directives(std::slice::from_ref(&_comment));
     }
 
-    fn add_leading_comments(self: &CountingComments, _pos: BytePos, _comments: Vec<Comment>) {
+    fn add_leading_comments(self: &CollectingComments, _pos: BytePos, _comments: Vec<Comment>) {
         let comment_count: usize = _comments
             .iter()
             .map(|comment| comment.text.matches('\n').count())
             .sum();
         self.count.set(self.count.get() + 1 + comment_count);
+        self.collect_directives(&_comments);
     }
 
-    fn add_trailing(self: &CountingComments, _pos: BytePos, _comment: Comment) {}
+    fn add_trailing(self: &CollectingComments, _pos: BytePos, _comment: Comment) {
+        self.collect_directives(std::slice::from_ref(&_comment));
+    }
 
-    fn add_trailing_comments(self: &CountingComments, _pos: BytePos, _comments: Vec<Comment>) {}
+    fn add_trailing_comments(self: &CollectingComments, _pos: BytePos, _comments: Vec<Comment>) {
+        self.collect_directives(&_comments);
+    }
 
     fn has_leading(&self, _pos: BytePos) -> bool {
         false
@@ -82,11 +223,11 @@ impl Comments for CountingComments {
         false
     }
 
-    fn take_leading(self: &CountingComments, _pos: BytePos) -> Option<Vec<Comment>> {
+    fn take_leading(self: &CollectingComments, _pos: BytePos) -> Option<Vec<Comment>> {
         None
     }
 
-    fn take_trailing(self: &CountingComments, _pos: BytePos) -> Option<Vec<Comment>> {
+    fn take_trailing(self: &CollectingComments, _pos: BytePos) -> Option<Vec<Comment>> {
         None
     }
 
@@ -110,14 +251,36 @@ impl Comments for CountingComments {
     fn add_pure_comment(&self, _pos: swc_common::BytePos) {}
 }
 
-impl CountingComments {
+impl CollectingComments {
     fn new() -> Self {
         Self {
             count: Cell::new(0),
+            directives: RefCell::new(Vec::new()),
         }
     }
 
     fn count(&self) -> usize {
         self.count.get()
     }
+
+    fn collect_directives(&self, comments: &[Comment]) {
+        let mut directives = self.directives.borrow_mut();
+        for comment in comments {
+            if let Some((kind, reason)) = parse_directive(&comment.text) {
+                directives.push(Directive {
+                    kind,
+                    reason,
+                    pos: comment.span.lo,
+                });
+            }
+        }
+    }
+
+    /// The directives in the order they appear in.
+    fn directives(&self) -> Vec<Directive> {
+        let mut directives = self.directives.take();
+        directives.sort_by_key(|directive| directive.pos);
+        directives.dedup_by_key(|directive| directive.pos);
+        directives
+    }
 }
@@ -1,6 +1,9 @@
 #[cfg(test)]
 mod tests {
-    use crate::parse::parse_module;
+    use crate::parse::{
+        parse_directive, parse_module, parse_module_with_directives, DirectiveKind, LineMap,
+    };
+    use swc_common::Spanned;
 
     #[test]
     fn test_parse_module() {
@@ -121,4 +124,76 @@ mod tests {
         );
         assert_eq!(line_count, 5, "Incorrect line count");
     }
+
+    #[test]
+    fn it_locates_syntax_errors_in_the_original_source() {
+        let ts_code = "const a = 1;\n\n\n  const b = ;\n";
+
+        let (parsed_module, _line_count) = parse_module(ts_code, false, false);
+        let err = parsed_module.unwrap_err();
+        let line_map = LineMap::new(ts_code);
+
+        assert_eq!(line_map.original_line(err.span().lo), 4);
+        assert_eq!(line_map.column(err.span().lo), 13);
+    }
+
+    #[test]
+    fn it_parses_directives() {
+        assert_eq!(
+            parse_directive(" fta-ignore-file"),
+            Some((DirectiveKind::IgnoreFile, None))
+        );
+        assert_eq!(
+            parse_directive(" fta-ignore-next-function -- dispatch table "),
+            Some((
+                DirectiveKind::IgnoreNextFunction,
+                Some("dispatch table".to_string())
+            ))
+        );
+        assert_eq!(
+            parse_directive("* fta-score-cap: 80 -- generated parser "),
+            Some((
+                DirectiveKind::ScoreCap(80),
+                Some("generated parser".to_string())
+            ))
+        );
+        assert_eq!(
+            parse_directive(" fta-score-cap:120"),
+            Some((DirectiveKind::ScoreCap(120), None))
+        );
+        assert_eq!(parse_directive(" fta-score-cap: high"), None);
+        assert_eq!(
+            parse_directive(" fta-ignore-file --"),
+            Some((DirectiveKind::IgnoreFile, None))
+        );
+        assert_eq!(parse_directive(" see fta-ignore-file"), None);
+        assert_eq!(parse_directive(" fta-ignore-files"), None);
+    }
+
+    #[test]
+    fn it_collects_directives_from_comments() {
+        let ts_code = "// fta-score-cap: 80\n\n/* fta-ignore-next-function -- table */\nfunction a() {}\n// a regular comment\nconst b = a(); // fta-ignore-file\n";
+
+        let (parsed_module, line_count, directives) =
+            parse_module_with_directives(ts_code, false, false);
+        let line_map = LineMap::new(ts_code);
+
+        assert!(parsed_module.is_ok());
+        assert_eq!(line_count, 2);
+        assert_eq!(
+            directives
+                .iter()
+                .map(|directive| (
+                    directive.kind,
+                    directive.reason.as_deref(),
+                    line_map.original_line(directive.pos)
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (DirectiveKind::ScoreCap(80), None, 1),
+                (DirectiveKind::IgnoreNextFunction, Some("table"), 3),
+                (DirectiveKind::IgnoreFile, None, 6),
+            ]
+        );
+    }
 }
@@ -0,0 +1,143 @@
+use std::ops::Range;
+
+mod tests;
+
+/// File extensions of single-file components, whose scripts are embedded in markup.
+const COMPONENT_EXTENSIONS: [&str; 3] = ["vue", "svelte", "astro"];
+
+/// The scripts of a single-file component, ready to be passed to `parse_module`.
+pub struct ComponentScript {
+    /// The original file with everything outside of the scripts replaced by whitespace. Line
+    /// breaks are kept, so line numbers still match the original file once `parse_module` drops
+    /// the blank lines.
+    pub source: String,
+    pub use_tsx: bool,
+}
+
+pub fn is_component(extension: &str) -> bool {
+    COMPONENT_EXTENSIONS.contains(&extension)
+}
+
+/// Extracts the `<script>` blocks of a Vue, Svelte or Astro component, plus the frontmatter of
+/// Astro components.
+pub fn extract_scripts(source: &str, extension: &str) -> ComponentScript {
+    let mut ranges = Vec::new();
+    let mut use_tsx = false;
+
+    let mut scripts_start = 0;
+    if extension == "astro" {
+        if let Some(frontmatter) = frontmatter_range(source) {
+            scripts_start = frontmatter.end;
+            ranges.push(frontmatter);
+        }
+    }
+
+    for (range, lang) in script_ranges(source, scripts_start) {
+        use_tsx |= matches!(lang, Some("tsx") | Some("jsx"));
+        ranges.push(range);
+    }
+
+    ComponentScript {
+        source: blank_outside(source, &ranges),
+        use_tsx,
+    }
+}
+
+/// The code between the `---` fences at the top of an Astro component.
+fn frontmatter_range(source: &str) -> Option<Range<usize>> {
+    let mut start = None;
+    let mut offset = 0;
+
+    for line in source.split_inclusive('\n') {
+        let end = offset + line.len();
+        match start {
+            None if line.trim().is_empty() => {}
+            None if line.trim_end() == "---" => start = Some(end),
+            None => return None,
+            Some(start) if line.trim_end() == "---" => return Some(start..offset),
+            Some(_) => {}
+        }
+        offset = end;
+    }
+
+    None
+}
+
+/// The contents of every `<script>` element after `from`, along with its `lang` attribute.
+fn script_ranges(source: &str, from: usize) -> Vec<(Range<usize>, Option<&str>)> {
+    const OPEN_TAG: &str = "<script";
+    const CLOSE_TAG: &str = "</script";
+
+    // ASCII lowercasing keeps byte offsets intact
+    let lower = source.to_ascii_lowercase();
+    let mut ranges = Vec::new();
+    let mut position = from;
+
+    while let Some(index) = lower[position..].find(OPEN_TAG) {
+        let attributes_start = position + index + OPEN_TAG.len();
+        position = attributes_start;
+
+        // Skip elements that merely start with "script", such as `<scripts>`
+        if !lower[attributes_start..].starts_with(|c: char| c == '>' || c.is_ascii_whitespace()) {
+            continue;
+        }
+        let Some(tag_end) = find_tag_end(source, attributes_start) else {
+            break;
+        };
+        let attributes = &source[attributes_start..tag_end];
+        position = tag_end + 1;
+
+        // Self-closing tags, such as `<script src="..." />`, have no content
+        if attributes.trim_end().ends_with('/') {
+            continue;
+        }
+        let Some(length) = lower[position..].find(CLOSE_TAG) else {
+            break;
+        };
+
+        ranges.push((position..position + length, lang_attribute(attributes)));
+        position += length + CLOSE_TAG.len();
+    }
+
+    ranges
+}
+
+/// Finds the `>` that ends a tag, skipping over any in quoted attribute values.
+fn find_tag_end(source: &str, from: usize) -> Option<usize> {
+    let mut quote = None;
+    for (index, c) in source[from..].char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '>') => return Some(from + index),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn lang_attribute(attributes: &str) -> Option<&str> {
+    let (before, after) = attributes.split_once("lang=")?;
+    if !before.is_empty() && !before.ends_with(|c: char| c.is_ascii_whitespace()) {
+        return None;
+    }
+
+    match after.chars().next()? {
+        quote @ ('"' | '\'') => after[1..].split(quote).next(),
+        _ => after.split(|c: char| c.is_ascii_whitespace()).next(),
+    }
+}
+
+fn blank_outside(source: &str, ranges: &[Range<usize>]) -> String {
+    source
+        .char_indices()
+        .map(|(index, c)| {
+            if c == '\n' || c == '\r' || ranges.iter().any(|range| range.contains(&index)) {
+                c
+            } else {
+                ' '
+            }
+        })
+        .collect()
+}
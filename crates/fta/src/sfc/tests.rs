@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use crate::functions::analyze_functions;
+    use crate::parse::{parse_module, LineMap};
+    use crate::sfc::{extract_scripts, is_component};
+
+    /// The non-blank lines of the extracted scripts, with their one-based line numbers.
+    fn script_lines(source: &str, extension: &str) -> Vec<(usize, String)> {
+        extract_scripts(source, extension)
+            .source
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| (index + 1, line.trim().to_string()))
+            .collect()
+    }
+
+    fn lines(expected: &[(usize, &str)]) -> Vec<(usize, String)> {
+        expected
+            .iter()
+            .map(|(line, code)| (*line, code.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_is_component() {
+        assert!(is_component("vue"));
+        assert!(is_component("svelte"));
+        assert!(is_component("astro"));
+        assert!(!is_component("ts"));
+    }
+
+    #[test]
+    fn test_vue_script_and_script_setup() {
+        let source = r#"<template>
+  <button @click="increment">{{ count }}</button>
+</template>
+
+<script lang="ts">
+export default { name: "Counter" };
+</script>
+
+<script setup lang="ts">
+import { ref } from "vue";
+const count = ref(0);
+</script>
+
+<style scoped>
+button { color: red; }
+</style>
+"#;
+
+        assert_eq!(
+            script_lines(source, "vue"),
+            lines(&[
+                (6, r#"export default { name: "Counter" };"#),
+                (10, r#"import { ref } from "vue";"#),
+                (11, "const count = ref(0);"),
+            ])
+        );
+        assert!(!extract_scripts(source, "vue").use_tsx);
+    }
+
+    #[test]
+    fn test_script_on_a_single_line() {
+        let source = r#"<script context="module">export const prerender = true;</script>
+<h1>Hello</h1>"#;
+
+        assert_eq!(
+            script_lines(source, "svelte"),
+            lines(&[(1, "export const prerender = true;")])
+        );
+    }
+
+    #[test]
+    fn test_tsx_lang_and_quoted_attributes() {
+        let source = r#"<script setup lang='tsx' generic="T extends Record<string, unknown>">
+const render = () => <div />;
+</script>"#;
+        let script = extract_scripts(source, "vue");
+
+        assert!(script.use_tsx);
+        assert_eq!(
+            script_lines(source, "vue"),
+            lines(&[(2, "const render = () => <div />;")])
+        );
+    }
+
+    #[test]
+    fn test_ignores_external_and_unrelated_tags() {
+        let source = r#"<scripts>not a script</scripts>
+<script src="./external.js" />
+<SCRIPT>
+const upper = true;
+</SCRIPT>"#;
+
+        assert_eq!(
+            script_lines(source, "svelte"),
+            lines(&[(4, "const upper = true;")])
+        );
+    }
+
+    #[test]
+    fn test_astro_frontmatter_and_scripts() {
+        let source = r#"
+---
+import Layout from "../layouts/Layout.astro";
+const { title } = Astro.props;
+---
+<Layout title={title}>
+  <h1>{title}</h1>
+</Layout>
+<script>
+document.querySelector("h1");
+</script>
+"#;
+
+        assert_eq!(
+            script_lines(source, "astro"),
+            lines(&[
+                (3, r#"import Layout from "../layouts/Layout.astro";"#),
+                (4, "const { title } = Astro.props;"),
+                (10, r#"document.querySelector("h1");"#),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_astro_without_frontmatter() {
+        let source = "<h1>---</h1>\n---\n";
+
+        assert!(script_lines(source, "astro").is_empty());
+    }
+
+    #[test]
+    fn test_function_lines_map_to_the_component() {
+        let source = r#"<template>
+  <p>{{ total }}</p>
+</template>
+
+<script setup lang="ts">
+function sum(values: number[]) {
+  let total = 0;
+  for (const value of values) {
+    total += value;
+  }
+  return total;
+}
+</script>
+"#;
+        let script = extract_scripts(source, "vue");
+        let (module, line_count) = parse_module(&script.source, script.use_tsx, false);
+        let functions = analyze_functions(&module.unwrap(), &LineMap::new(&script.source));
+
+        assert_eq!(line_count, 7);
+        assert_eq!(functions[0].name, "sum");
+        assert_eq!(functions[0].start_line, 6);
+        assert_eq!(functions[0].end_line, 12);
+    }
+}
@@ -1,6 +1,75 @@
-use serde::{Deserialize, Serialize};
+use crate::config::{check_assessment_options, OverrideMatcher};
+use crate::error::ParseFailure;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+
+/// The scores above which files are assessed as "Could be better" and "Needs improvement".
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AssessmentThresholds {
+    pub could_be_better: f64,
+    pub needs_improvement: f64,
+}
+
+impl Default for AssessmentThresholds {
+    fn default() -> Self {
+        AssessmentThresholds {
+            could_be_better: 50.0,
+            needs_improvement: 60.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    None,
+    Note,
+    Warning,
+    Error,
+}
+
+/// A labelled range of scores. A file is assessed by the band with the highest `above` that its
+/// score exceeds, and scores below every band fall into the lowest one.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct AssessmentBand {
+    pub label: String,
+    pub above: Option<f64>,
+    #[serde(default)]
+    pub severity: Severity,
+}
+
+/// Options that apply to the files matching any of the `files` glob patterns.
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+#[serde(remote = "Self", deny_unknown_fields)]
+pub struct ConfigOverride {
+    #[serde(deserialize_with = "crate::config::deserialize_globs")]
+    pub files: Vec<String>,
+    pub score_cap: Option<usize>,
+    pub include_comments: Option<bool>,
+    pub exclude_under: Option<usize>,
+    pub assessment_thresholds: Option<AssessmentThresholds>,
+    #[serde(default, deserialize_with = "crate::config::deserialize_bands")]
+    pub assessment_bands: Option<Vec<AssessmentBand>>,
+    #[serde(skip)]
+    pub matcher: OverrideMatcher,
+}
+
+impl<'de> Deserialize<'de> for ConfigOverride {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config_override = ConfigOverride::deserialize(deserializer)?;
+        check_assessment_options(
+            &config_override.assessment_thresholds,
+            &config_override.assessment_bands,
+        )?;
+        Ok(config_override)
+    }
+}
 
 #[derive(Debug, Deserialize, Default)]
+#[serde(remote = "Self", deny_unknown_fields)]
 pub struct FtaConfigOptional {
     pub extensions: Option<Vec<String>>,
     pub exclude_filenames: Option<Vec<String>>,
@@ -9,9 +78,23 @@ pub struct FtaConfigOptional {
     pub score_cap: Option<usize>,
     pub include_comments: Option<bool>,
     pub exclude_under: Option<usize>,
+    pub baseline_tolerance: Option<f64>,
+    pub threads: Option<usize>,
+    pub assessment_thresholds: Option<AssessmentThresholds>,
+    #[serde(default, deserialize_with = "crate::config::deserialize_bands")]
+    pub assessment_bands: Option<Vec<AssessmentBand>>,
+    pub overrides: Option<Vec<ConfigOverride>>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+impl<'de> Deserialize<'de> for FtaConfigOptional {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = FtaConfigOptional::deserialize(deserializer)?;
+        check_assessment_options(&config.assessment_thresholds, &config.assessment_bands)?;
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
 pub struct FtaConfigResolved {
     pub extensions: Vec<String>,
     pub exclude_filenames: Vec<String>,
@@ -20,9 +103,18 @@ pub struct FtaConfigResolved {
     pub score_cap: usize,
     pub include_comments: bool,
     pub exclude_under: usize,
+    pub baseline_tolerance: f64,
+    pub threads: usize,
+    pub assessment_thresholds: AssessmentThresholds,
+    /// Custom bands, which take precedence over `assessment_thresholds`.
+    pub assessment_bands: Option<Vec<AssessmentBand>>,
+    pub overrides: Vec<ConfigOverride>,
+    /// Where analysis results are cached between runs, if at all.
+    #[serde(default)]
+    pub cache_dir: Option<String>,
 }
 
-#[derive(Debug, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct HalsteadMetrics {
     pub uniq_operators: usize,  // number of unique operators
     pub uniq_operands: usize,   // number of unique operands
@@ -37,12 +129,140 @@ pub struct HalsteadMetrics {
     pub bugs: f64,
 }
 
-#[derive(Debug, Serialize)]
+/// An inline comment that opts a file or function out of checks, on the given (one-based) line.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Suppression {
+    /// `// fta-ignore-file`, which exempts the file from the score cap, baselines and diagnostics.
+    IgnoreFile { line: usize, reason: Option<String> },
+    /// `// fta-ignore-next-function`, which exempts the following function from diagnostics.
+    IgnoreFunction { line: usize, reason: Option<String> },
+    /// `// fta-score-cap: <score cap>`, which replaces the configured score cap of the file.
+    ScoreCap {
+        line: usize,
+        score_cap: usize,
+        reason: Option<String>,
+    },
+}
+
+impl Suppression {
+    pub fn line(&self) -> usize {
+        match self {
+            Suppression::IgnoreFile { line, .. }
+            | Suppression::IgnoreFunction { line, .. }
+            | Suppression::ScoreCap { line, .. } => *line,
+        }
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        match self {
+            Suppression::IgnoreFile { reason, .. }
+            | Suppression::IgnoreFunction { reason, .. }
+            | Suppression::ScoreCap { reason, .. } => reason.as_deref(),
+        }
+    }
+}
+
+impl fmt::Display for Suppression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Suppression::IgnoreFile { .. } => write!(f, "fta-ignore-file")?,
+            Suppression::IgnoreFunction { .. } => write!(f, "fta-ignore-next-function")?,
+            Suppression::ScoreCap { score_cap, .. } => write!(f, "fta-score-cap: {}", score_cap)?,
+        }
+        match self.reason() {
+            Some(reason) => write!(f, " ({})", reason),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionData {
+    pub name: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub cyclo: usize,
+    pub cognitive: usize,
+    pub halstead: HalsteadMetrics,
+    pub line_count: usize,
+    pub fta_score: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suppression: Option<Suppression>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileData {
     pub file_name: String,
     pub cyclo: usize,
+    // Reports of earlier versions lack the fields that were added since
+    #[serde(default)]
+    pub cognitive: usize,
     pub halstead: HalsteadMetrics,
     pub line_count: usize,
     pub fta_score: f64,
     pub assessment: String,
+    #[serde(default)]
+    pub severity: Severity,
+    #[serde(default)]
+    pub functions: Vec<FunctionData>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suppression: Option<Suppression>,
+}
+
+impl FileData {
+    pub fn is_ignored(&self) -> bool {
+        matches!(self.suppression, Some(Suppression::IgnoreFile { .. }))
+    }
+
+    /// The score cap of the file, given the configured one. Ignored files have no score cap.
+    pub fn score_cap(&self, configured_score_cap: usize) -> Option<usize> {
+        match self.suppression {
+            Some(Suppression::IgnoreFile { .. }) => None,
+            Some(Suppression::ScoreCap { score_cap, .. }) => Some(score_cap),
+            _ => Some(configured_score_cap),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnalysisReport {
+    pub files: Vec<FileData>,
+    /// Files that couldn't be parsed, and are missing from `files`.
+    pub failures: Vec<ParseFailure>,
+}
+
+/// Placeholder Halstead metrics, for tests.
+#[cfg(test)]
+pub(crate) fn test_halstead() -> HalsteadMetrics {
+    HalsteadMetrics {
+        uniq_operators: 1,
+        uniq_operands: 2,
+        total_operators: 3,
+        total_operands: 4,
+        program_length: 5,
+        vocabulary_size: 6,
+        volume: 7.0,
+        difficulty: 8.0,
+        effort: 9.0,
+        time: 10.0,
+        bugs: 11.0,
+    }
+}
+
+/// A file with the given name and score and placeholder metrics, for tests.
+#[cfg(test)]
+pub(crate) fn test_file_data(file_name: &str, fta_score: f64) -> FileData {
+    FileData {
+        file_name: file_name.to_string(),
+        cyclo: 1,
+        cognitive: 0,
+        halstead: test_halstead(),
+        line_count: 10,
+        fta_score,
+        assessment: "OK".to_string(),
+        severity: Severity::None,
+        functions: vec![],
+        suppression: None,
+    }
 }
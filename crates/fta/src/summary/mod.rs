@@ -0,0 +1,177 @@
+use crate::config::assessment_bands;
+use crate::error::ParseFailure;
+use crate::structs::{FileData, FtaConfigResolved, Severity, Suppression};
+use crate::utils::{median, percentile};
+use serde::Serialize;
+
+mod tests;
+
+/// The score distribution is split into buckets of this width, from 0 up to `HISTOGRAM_BUCKETS`
+/// buckets. Scores beyond the last bucket are counted in a final, open-ended bucket.
+const HISTOGRAM_BUCKET_WIDTH: f64 = 10.0;
+const HISTOGRAM_BUCKETS: usize = 10;
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ScoreStatistics {
+    pub mean: f64,
+    pub median: f64,
+    pub p75: f64,
+    pub p90: f64,
+    pub p95: f64,
+    pub max: f64,
+}
+
+/// The number of files with a score of at least `min` and below `max`.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct HistogramBucket {
+    pub min: f64,
+    pub max: Option<f64>,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct AssessmentCount {
+    pub assessment: String,
+    pub severity: Severity,
+    pub count: usize,
+}
+
+/// A file or function that opts out of checks with an inline comment.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Suppressed {
+    pub file_name: String,
+    /// The suppressed function, if only a function is suppressed.
+    pub function: Option<String>,
+    #[serde(flatten)]
+    pub suppression: Suppression,
+}
+
+/// Totals and the score distribution of an analysis.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Summary {
+    pub file_count: usize,
+    pub line_count: usize,
+    /// Files that couldn't be parsed, and are missing from the report.
+    pub failures: Vec<ParseFailure>,
+    pub suppressed: Vec<Suppressed>,
+    /// The time the analysis took, in seconds.
+    pub elapsed: f64,
+    /// Statistics of the FTA scores, if any files were analyzed.
+    pub scores: Option<ScoreStatistics>,
+    pub histogram: Vec<HistogramBucket>,
+    /// The number of files in each assessment band, in order of the bands.
+    pub assessments: Vec<AssessmentCount>,
+}
+
+pub fn score_statistics(files: &[FileData]) -> Option<ScoreStatistics> {
+    if files.is_empty() {
+        return None;
+    }
+
+    let mut scores: Vec<f64> = files.iter().map(|file_data| file_data.fta_score).collect();
+    scores.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    Some(ScoreStatistics {
+        mean: scores.iter().sum::<f64>() / scores.len() as f64,
+        median: median(&scores),
+        p75: percentile(&scores, 75.0),
+        p90: percentile(&scores, 90.0),
+        p95: percentile(&scores, 95.0),
+        max: scores[scores.len() - 1],
+    })
+}
+
+fn histogram(files: &[FileData]) -> Vec<HistogramBucket> {
+    let mut buckets: Vec<HistogramBucket> = (0..=HISTOGRAM_BUCKETS)
+        .map(|bucket| HistogramBucket {
+            min: bucket as f64 * HISTOGRAM_BUCKET_WIDTH,
+            max: (bucket < HISTOGRAM_BUCKETS).then(|| (bucket + 1) as f64 * HISTOGRAM_BUCKET_WIDTH),
+            count: 0,
+        })
+        .collect();
+
+    for file_data in files {
+        let bucket = (file_data.fta_score / HISTOGRAM_BUCKET_WIDTH).floor() as usize;
+        buckets[bucket.min(HISTOGRAM_BUCKETS)].count += 1;
+    }
+
+    buckets
+}
+
+/// Counts the files in each of the configured bands, lowest first. Bands that only apply to some
+/// files through overrides are listed after them, in the order they were first seen.
+fn assessment_counts(files: &[FileData], config: &FtaConfigResolved) -> Vec<AssessmentCount> {
+    let mut bands = assessment_bands(config);
+    bands.sort_by(|a, b| a.above.partial_cmp(&b.above).unwrap());
+
+    let mut counts: Vec<AssessmentCount> = bands
+        .into_iter()
+        .map(|band| AssessmentCount {
+            assessment: band.label,
+            severity: band.severity,
+            count: 0,
+        })
+        .collect();
+
+    for file_data in files {
+        match counts
+            .iter_mut()
+            .find(|count| count.assessment == file_data.assessment)
+        {
+            Some(count) => count.count += 1,
+            None => counts.push(AssessmentCount {
+                assessment: file_data.assessment.clone(),
+                severity: file_data.severity,
+                count: 1,
+            }),
+        }
+    }
+
+    counts
+}
+
+fn suppressed(files: &[FileData]) -> Vec<Suppressed> {
+    let mut suppressed = Vec::new();
+
+    for file_data in files {
+        if let Some(suppression) = &file_data.suppression {
+            suppressed.push(Suppressed {
+                file_name: file_data.file_name.clone(),
+                function: None,
+                suppression: suppression.clone(),
+            });
+        }
+        for function in &file_data.functions {
+            if let Some(suppression) = &function.suppression {
+                suppressed.push(Suppressed {
+                    file_name: file_data.file_name.clone(),
+                    function: Some(function.name.clone()),
+                    suppression: suppression.clone(),
+                });
+            }
+        }
+    }
+    suppressed.sort_by(|a, b| {
+        (&a.file_name, a.suppression.line()).cmp(&(&b.file_name, b.suppression.line()))
+    });
+
+    suppressed
+}
+
+pub fn summarize(
+    files: &[FileData],
+    failures: &[ParseFailure],
+    elapsed: f64,
+    config: &FtaConfigResolved,
+) -> Summary {
+    Summary {
+        file_count: files.len(),
+        line_count: files.iter().map(|file_data| file_data.line_count).sum(),
+        failures: failures.to_vec(),
+        suppressed: suppressed(files),
+        elapsed,
+        scores: score_statistics(files),
+        histogram: histogram(files),
+        assessments: assessment_counts(files, config),
+    }
+}
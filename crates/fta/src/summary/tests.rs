@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod tests {
+    use crate::config::get_default_config;
+    use crate::error::ParseFailure;
+    use crate::structs::{test_file_data, AssessmentBand, FileData, Severity};
+    use crate::summary::{summarize, AssessmentCount, ScoreStatistics};
+
+    fn file_data(fta_score: f64, assessment: &str, severity: Severity) -> FileData {
+        FileData {
+            assessment: assessment.to_string(),
+            severity,
+            ..test_file_data(&format!("{}.ts", fta_score), fta_score)
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let files = vec![
+            file_data(12.5, "OK", Severity::None),
+            file_data(35.0, "OK", Severity::None),
+            file_data(38.0, "OK", Severity::None),
+            file_data(55.0, "Could be better", Severity::Warning),
+            file_data(140.0, "Needs improvement", Severity::Error),
+        ];
+
+        let failures = vec![ParseFailure {
+            file_name: "broken.ts".to_string(),
+            message: "Expression expected".to_string(),
+            line: 2,
+            column: 5,
+        }];
+
+        let summary = summarize(&files, &failures, 0.5, &get_default_config());
+
+        assert_eq!(summary.file_count, 5);
+        assert_eq!(summary.line_count, 50);
+        assert_eq!(summary.failures, failures);
+        assert_eq!(summary.elapsed, 0.5);
+        assert_eq!(
+            summary.scores,
+            Some(ScoreStatistics {
+                mean: 56.1,
+                median: 38.0,
+                p75: 55.0,
+                p90: 140.0,
+                p95: 140.0,
+                max: 140.0,
+            })
+        );
+        assert_eq!(
+            summary
+                .histogram
+                .iter()
+                .map(|bucket| (bucket.min, bucket.max, bucket.count))
+                .filter(|(_, _, count)| *count > 0)
+                .collect::<Vec<_>>(),
+            vec![
+                (10.0, Some(20.0), 1),
+                (30.0, Some(40.0), 2),
+                (50.0, Some(60.0), 1),
+                (100.0, None, 1),
+            ]
+        );
+        assert_eq!(summary.histogram.len(), 11);
+        assert_eq!(
+            summary
+                .assessments
+                .iter()
+                .map(|count| (count.assessment.as_str(), count.count))
+                .collect::<Vec<_>>(),
+            vec![("OK", 3), ("Could be better", 1), ("Needs improvement", 1)]
+        );
+    }
+
+    #[test]
+    fn test_summarize_without_files() {
+        let summary = summarize(&[], &[], 0.1, &get_default_config());
+
+        assert_eq!(summary.file_count, 0);
+        assert_eq!(summary.scores, None);
+        assert!(summary.histogram.iter().all(|bucket| bucket.count == 0));
+        assert!(summary.assessments.iter().all(|count| count.count == 0));
+    }
+
+    #[test]
+    fn test_summarize_counts_custom_bands() {
+        let mut config = get_default_config();
+        config.assessment_bands = Some(vec![
+            AssessmentBand {
+                label: "Risky".to_string(),
+                above: Some(50.0),
+                severity: Severity::Warning,
+            },
+            AssessmentBand {
+                label: "Fine".to_string(),
+                above: None,
+                severity: Severity::None,
+            },
+        ]);
+        let files = vec![
+            file_data(20.0, "Fine", Severity::None),
+            file_data(60.0, "Risky", Severity::Warning),
+            // Assessed by the bands of an override
+            file_data(90.0, "Legacy", Severity::Note),
+        ];
+
+        assert_eq!(
+            summarize(&files, &[], 0.1, &config).assessments,
+            vec![
+                AssessmentCount {
+                    assessment: "Fine".to_string(),
+                    severity: Severity::None,
+                    count: 1,
+                },
+                AssessmentCount {
+                    assessment: "Risky".to_string(),
+                    severity: Severity::Warning,
+                    count: 1,
+                },
+                AssessmentCount {
+                    assessment: "Legacy".to_string(),
+                    severity: Severity::Note,
+                    count: 1,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use crate::parse::{Directive, DirectiveKind, LineMap};
+use crate::structs::{FileData, Suppression};
+
+mod tests;
+
+/// Marks the file and its functions as suppressed according to the directives in its comments.
+/// `fta-ignore-file` takes precedence over `fta-score-cap`, and otherwise the last directive wins.
+pub fn apply_directives(file_data: &mut FileData, directives: &[Directive], line_map: &LineMap) {
+    for directive in directives {
+        let line = line_map.original_line(directive.pos);
+        let reason = directive.reason.clone();
+
+        match directive.kind {
+            DirectiveKind::IgnoreFile => {
+                file_data.suppression = Some(Suppression::IgnoreFile { line, reason });
+            }
+            DirectiveKind::ScoreCap(score_cap) if !file_data.is_ignored() => {
+                file_data.suppression = Some(Suppression::ScoreCap {
+                    line,
+                    score_cap,
+                    reason,
+                });
+            }
+            DirectiveKind::ScoreCap(_) => {}
+            DirectiveKind::IgnoreNextFunction => {
+                // Functions are listed outermost first, so this picks the outermost function
+                // starting on the first line after the comment
+                let function = file_data
+                    .functions
+                    .iter_mut()
+                    .filter(|function| function.start_line > line)
+                    .min_by_key(|function| function.start_line);
+                if let Some(function) = function {
+                    function.suppression = Some(Suppression::IgnoreFunction { line, reason });
+                }
+            }
+        }
+    }
+}
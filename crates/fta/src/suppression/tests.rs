@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod tests {
+    use crate::analyze_source;
+    use crate::config::get_default_config;
+    use crate::structs::Suppression;
+
+    const SOURCE: &str = "function first(a: number) {\n  return a > 1 ? a : 1;\n}\n\n// fta-ignore-next-function -- lookup table\nexport function second(b: number) {\n  const inner = () => b;\n  return inner();\n}\nfunction third() {}\n";
+
+    #[test]
+    fn test_ignore_next_function() {
+        let file_data = analyze_source("a.ts", SOURCE, &get_default_config()).unwrap();
+
+        assert_eq!(file_data.suppression, None);
+        assert_eq!(
+            file_data
+                .functions
+                .iter()
+                .map(|function| (function.name.as_str(), function.suppression.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("first", None),
+                (
+                    "second",
+                    Some(Suppression::IgnoreFunction {
+                        line: 5,
+                        reason: Some("lookup table".to_string()),
+                    })
+                ),
+                ("inner", None),
+                ("third", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_file_suppressions() {
+        let config = get_default_config();
+        let score_cap = format!("// fta-score-cap: 80\n{}", SOURCE);
+        let ignored = format!("{}// fta-ignore-file -- generated\n", score_cap);
+
+        let file_data = analyze_source("a.ts", &score_cap, &config).unwrap();
+        assert_eq!(
+            file_data.suppression,
+            Some(Suppression::ScoreCap {
+                line: 1,
+                score_cap: 80,
+                reason: None,
+            })
+        );
+        assert_eq!(file_data.score_cap(config.score_cap), Some(80));
+        assert!(!file_data.is_ignored());
+
+        // Ignoring the file takes precedence over its score cap
+        let file_data = analyze_source("a.ts", &ignored, &config).unwrap();
+        assert_eq!(
+            file_data.suppression,
+            Some(Suppression::IgnoreFile {
+                line: 12,
+                reason: Some("generated".to_string()),
+            })
+        );
+        assert_eq!(file_data.score_cap(config.score_cap), None);
+        assert!(file_data.is_ignored());
+    }
+
+    #[test]
+    fn test_suppression_display() {
+        assert_eq!(
+            Suppression::ScoreCap {
+                line: 1,
+                score_cap: 80,
+                reason: Some("state machine".to_string()),
+            }
+            .to_string(),
+            "fta-score-cap: 80 (state machine)"
+        );
+        assert_eq!(
+            Suppression::IgnoreFile {
+                line: 1,
+                reason: None,
+            }
+            .to_string(),
+            "fta-ignore-file"
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::structs::FtaConfigResolved;
+use crate::structs::{AssessmentBand, FtaConfigResolved, Severity};
 use globset::{Glob, GlobSetBuilder};
 use ignore::DirEntry;
 use log::warn;
@@ -89,6 +89,21 @@ pub fn is_valid_file(repo_path: &String, entry: &DirEntry, config: &FtaConfigRes
         .to_str()
         .unwrap();
 
+    is_included(file_name, relative_path, config)
+}
+
+/// Like `is_valid_file`, for a path relative to the project that may not exist on disk.
+#[cfg(feature = "lsp")]
+pub fn is_valid_path(relative_path: &str, config: &FtaConfigResolved) -> bool {
+    let file_name = Path::new(relative_path)
+        .file_name()
+        .and_then(|file_name| file_name.to_str())
+        .unwrap_or_default();
+
+    is_included(file_name, relative_path, config)
+}
+
+fn is_included(file_name: &str, relative_path: &str, config: &FtaConfigResolved) -> bool {
     let valid_extension = config.extensions.iter().any(|ext| file_name.ends_with(ext));
     let is_excluded_filename = is_excluded_filename(file_name, &config.exclude_filenames);
     let is_excluded_directory =
@@ -109,23 +124,37 @@ pub fn warn_about_language(file_name: &str, use_tsx: bool) {
     );
 }
 
-pub fn check_score_cap_breach(file_name: String, fta_score: f64, score_cap: usize) {
-    // Exit 1 if score_cap breached
-    if fta_score > score_cap as f64 {
-        eprintln!(
-            "File {} has a score of {}, which is beyond the score cap of {}, exiting.",
-            file_name, fta_score, score_cap
-        );
-        std::process::exit(1);
+pub fn get_assessment(score: f64, bands: &[AssessmentBand]) -> (String, Severity) {
+    let threshold = |band: &&AssessmentBand| band.above.unwrap_or(f64::NEG_INFINITY);
+
+    let band = bands
+        .iter()
+        .filter(|band| score > threshold(band))
+        .max_by(|a, b| threshold(a).total_cmp(&threshold(b)))
+        .or_else(|| {
+            bands
+                .iter()
+                .min_by(|a, b| threshold(a).total_cmp(&threshold(b)))
+        });
+
+    match band {
+        Some(band) => (band.label.clone(), band.severity),
+        None => ("OK".to_string(), Severity::None),
     }
 }
 
-pub fn get_assessment(score: f64) -> String {
-    if score > 60.0 {
-        "Needs improvement".to_string()
-    } else if score > 50.0 {
-        "Could be better".to_string()
+/// The score below which `percentile` percent of the (sorted, non-empty) scores fall, using the
+/// nearest rank.
+pub fn percentile(sorted_scores: &[f64], percentile: f64) -> f64 {
+    let rank = (percentile / 100.0 * sorted_scores.len() as f64).ceil() as usize;
+    sorted_scores[rank.max(1) - 1]
+}
+
+pub fn median(sorted_scores: &[f64]) -> f64 {
+    let middle = sorted_scores.len() / 2;
+    if sorted_scores.len().is_multiple_of(2) {
+        (sorted_scores[middle - 1] + sorted_scores[middle]) / 2.0
     } else {
-        "OK".to_string()
+        sorted_scores[middle]
     }
 }
@@ -1,23 +1,91 @@
 #[cfg(test)]
 mod tests {
-    use crate::utils::{get_assessment, is_excluded_directory_path, is_excluded_filename};
+    use crate::config::{assessment_bands, get_default_config};
+    use crate::structs::{AssessmentBand, AssessmentThresholds, Severity};
+    use crate::utils::{
+        get_assessment, is_excluded_directory_path, is_excluded_filename, median, percentile,
+    };
+
+    fn default_bands() -> Vec<AssessmentBand> {
+        assessment_bands(&get_default_config())
+    }
+
+    fn band(label: &str, above: Option<f64>, severity: Severity) -> AssessmentBand {
+        AssessmentBand {
+            label: label.to_string(),
+            above,
+            severity,
+        }
+    }
 
     #[test]
     fn test_get_assessment_ok() {
-        let assessment = get_assessment(45.0);
-        assert_eq!(assessment, "OK");
+        let assessment = get_assessment(45.0, &default_bands());
+        assert_eq!(assessment, ("OK".to_string(), Severity::None));
     }
 
     #[test]
     fn test_get_assessment_could_be_better() {
-        let assessment = get_assessment(60.0);
-        assert_eq!(assessment, "Could be better");
+        let assessment = get_assessment(60.0, &default_bands());
+        assert_eq!(
+            assessment,
+            ("Could be better".to_string(), Severity::Warning)
+        );
     }
 
     #[test]
     fn test_get_assessment_needs_improvement() {
-        let assessment = get_assessment(75.0);
-        assert_eq!(assessment, "Needs improvement");
+        let assessment = get_assessment(75.0, &default_bands());
+        assert_eq!(
+            assessment,
+            ("Needs improvement".to_string(), Severity::Error)
+        );
+    }
+
+    #[test]
+    fn test_get_assessment_with_custom_thresholds() {
+        let mut config = get_default_config();
+        config.assessment_thresholds = AssessmentThresholds {
+            could_be_better: 30.0,
+            needs_improvement: 40.0,
+        };
+        let bands = assessment_bands(&config);
+
+        assert_eq!(get_assessment(30.0, &bands).0, "OK");
+        assert_eq!(get_assessment(35.0, &bands).0, "Could be better");
+        assert_eq!(get_assessment(45.0, &bands).0, "Needs improvement");
+    }
+
+    #[test]
+    fn test_get_assessment_with_custom_bands() {
+        // Bands don't need to be in order
+        let bands = vec![
+            band("Critical", Some(80.0), Severity::Error),
+            band("Great", Some(10.0), Severity::None),
+            band("Fine", Some(40.0), Severity::Note),
+            band("Risky", Some(60.0), Severity::Warning),
+        ];
+
+        assert_eq!(
+            get_assessment(5.0, &bands),
+            ("Great".to_string(), Severity::None)
+        );
+        assert_eq!(
+            get_assessment(40.0, &bands),
+            ("Great".to_string(), Severity::None)
+        );
+        assert_eq!(
+            get_assessment(40.5, &bands),
+            ("Fine".to_string(), Severity::Note)
+        );
+        assert_eq!(
+            get_assessment(79.0, &bands),
+            ("Risky".to_string(), Severity::Warning)
+        );
+        assert_eq!(
+            get_assessment(120.0, &bands),
+            ("Critical".to_string(), Severity::Error)
+        );
     }
 
     #[test]
@@ -279,4 +347,15 @@ mod tests {
             false
         ); // Not "packages/legacy"
     }
+
+    #[test]
+    fn test_median_and_percentile() {
+        let scores = [10.0, 20.0, 30.0, 40.0];
+        assert_eq!(median(&scores), 25.0);
+        assert_eq!(median(&scores[..3]), 20.0);
+        assert_eq!(percentile(&scores, 50.0), 20.0);
+        assert_eq!(percentile(&scores, 90.0), 40.0);
+        assert_eq!(percentile(&scores, 0.0), 10.0);
+        assert_eq!(percentile(&[5.0], 95.0), 5.0);
+    }
 }
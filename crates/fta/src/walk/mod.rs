@@ -1,31 +1,61 @@
+use crate::error::ParseFailure;
 use crate::structs::{FileData, FtaConfigResolved};
-use ignore::DirEntry;
+use ignore::{DirEntry, WalkParallel, WalkState};
+use std::sync::Mutex;
 
-pub fn walk_and_analyze_files<I, P, V>(
-    entries: I,
+mod tests;
+
+/// Analyzes every valid file of the walk, returning the analyzed files along with the files that
+/// couldn't be parsed.
+pub fn walk_and_analyze_files<P, V>(
+    walk: WalkParallel,
     repo_path: &String,
     config: &FtaConfigResolved,
     process_entry: P,
     is_valid: V,
-) -> Vec<FileData>
+) -> (Vec<FileData>, Vec<ParseFailure>)
 where
-    I: Iterator<Item = Result<DirEntry, ignore::Error>>,
-    P: Fn(DirEntry, &String, &FtaConfigResolved) -> Option<Vec<FileData>>,
-    V: Fn(&String, &DirEntry, &FtaConfigResolved) -> bool,
+    P: Fn(DirEntry, &String, &FtaConfigResolved) -> Result<Vec<FileData>, ParseFailure> + Sync,
+    V: Fn(&String, &DirEntry, &FtaConfigResolved) -> bool + Sync,
 {
-    let mut file_data_list: Vec<FileData> = Vec::new();
-
-    entries
-        // 1. Were we able to successfully read the DirEntry & is it a file?
-        .filter(|entry| entry.is_ok())
-        .map(|entry| entry.unwrap())
-        .filter(|entry| entry.file_type().unwrap().is_file())
-        // 2. Is the file considered valid according to our basic requirements plus user configuration?
-        .filter(|entry| is_valid(repo_path, &entry, config))
-        // 3. Analyze each file
-        .filter_map(|entry| process_entry(entry, repo_path, config))
-        // 4. Return a list of analyzed files
-        .for_each(|data_vec| file_data_list.extend(data_vec));
-
-    file_data_list
+    let file_data_list: Mutex<Vec<FileData>> = Mutex::new(Vec::new());
+    let failures: Mutex<Vec<ParseFailure>> = Mutex::new(Vec::new());
+
+    walk.run(|| {
+        Box::new(|entry| {
+            // 1. Were we able to successfully read the DirEntry & is it a file?
+            let entry = match entry {
+                Ok(entry)
+                    if entry
+                        .file_type()
+                        .is_some_and(|file_type| file_type.is_file()) =>
+                {
+                    entry
+                }
+                _ => return WalkState::Continue,
+            };
+
+            // 2. Is the file considered valid according to our basic requirements plus user configuration?
+            if !is_valid(repo_path, &entry, config) {
+                return WalkState::Continue;
+            }
+
+            // 3. Analyze each file
+            match process_entry(entry, repo_path, config) {
+                Ok(data_vec) => file_data_list.lock().unwrap().extend(data_vec),
+                Err(failure) => failures.lock().unwrap().push(failure),
+            }
+
+            WalkState::Continue
+        })
+    });
+
+    // 4. Return a list of analyzed files. Files finish in whichever order the threads get to them,
+    // so sort them to keep the output deterministic.
+    let mut file_data_list = file_data_list.into_inner().unwrap();
+    file_data_list.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    let mut failures = failures.into_inner().unwrap();
+    failures.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+
+    (file_data_list, failures)
 }
@@ -0,0 +1,193 @@
+#[cfg(test)]
+mod tests {
+    use crate::config::get_default_config;
+    use crate::error::{FtaError, ParseFailure};
+    use crate::structs::{test_file_data, FileData, FtaConfigResolved};
+    use crate::walk::walk_and_analyze_files;
+    use ignore::{DirEntry, WalkBuilder};
+    use std::collections::HashSet;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn file_data(file_name: String) -> FileData {
+        test_file_data(&file_name, 10.0)
+    }
+
+    fn process_entry(
+        entry: DirEntry,
+        repo_path: &String,
+        _config: &FtaConfigResolved,
+    ) -> Result<Vec<FileData>, ParseFailure> {
+        let file_name = entry
+            .path()
+            .strip_prefix(repo_path)
+            .unwrap()
+            .display()
+            .to_string();
+        if file_name.ends_with("13.ts") {
+            return Err(ParseFailure {
+                file_name,
+                message: "Unexpected token".to_string(),
+                line: 1,
+                column: 1,
+            });
+        }
+        Ok(vec![file_data(file_name)])
+    }
+
+    fn is_valid(_repo_path: &String, entry: &DirEntry, _config: &FtaConfigResolved) -> bool {
+        entry.path().extension().is_some_and(|ext| ext == "ts")
+    }
+
+    fn create_project() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        for directory in ["a", "b", "c"] {
+            fs::create_dir(dir.path().join(directory)).unwrap();
+            for file in 0..20 {
+                let path = dir.path().join(directory).join(format!("{:02}.ts", file));
+                fs::write(path, "const x = 1;").unwrap();
+            }
+        }
+        fs::write(dir.path().join("README.md"), "# readme").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_walk_and_analyze_files_is_deterministic() {
+        let dir = create_project();
+        let repo_path = dir.path().to_str().unwrap().to_string();
+        let mut config = get_default_config();
+
+        let mut results = Vec::new();
+        for threads in [1, 4, 0] {
+            config.threads = threads;
+            let walk = WalkBuilder::new(&repo_path)
+                .threads(config.threads)
+                .build_parallel();
+            let (files, failures) =
+                walk_and_analyze_files(walk, &repo_path, &config, process_entry, is_valid);
+            let failed_file_names: Vec<String> = failures
+                .into_iter()
+                .map(|failure| failure.file_name)
+                .collect();
+            assert_eq!(
+                failed_file_names,
+                ["a", "b", "c"].map(|directory| format!(
+                    "{}{}13.ts",
+                    directory,
+                    std::path::MAIN_SEPARATOR
+                ))
+            );
+            let file_names: Vec<String> = files
+                .into_iter()
+                .map(|file_data| file_data.file_name)
+                .collect();
+            results.push(file_names);
+        }
+
+        assert_eq!(results[0].len(), 57);
+        assert_eq!(
+            results[0][0],
+            format!("a{}00.ts", std::path::MAIN_SEPARATOR)
+        );
+        assert!(results[0].windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(results[0], results[1]);
+        assert_eq!(results[0], results[2]);
+    }
+
+    #[test]
+    fn test_analyze_files() {
+        let dir = create_project();
+        fs::write(dir.path().join(".gitignore"), "b/\n").unwrap();
+        fs::write(dir.path().join("b").join("broken.ts"), "function (").unwrap();
+        let repo_path = dir.path().to_str().unwrap().to_string();
+        let mut config = get_default_config();
+        config.exclude_under = 0;
+
+        let files: HashSet<PathBuf> = [
+            PathBuf::from("a/01.ts"),
+            dir.path().join("b").join("02.ts"),
+            PathBuf::from("b/broken.ts"),
+            PathBuf::from("README.md"),
+            PathBuf::from("c/missing.ts"),
+        ]
+        .into_iter()
+        .collect();
+        let report = crate::analyze_files(&repo_path, &config, &files).unwrap();
+
+        // Listed files are analyzed even if they're ignored, but other rules still apply
+        assert_eq!(
+            report
+                .files
+                .iter()
+                .map(|file_data| file_data.file_name.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                format!("a{}01.ts", std::path::MAIN_SEPARATOR),
+                format!("b{}02.ts", std::path::MAIN_SEPARATOR)
+            ]
+        );
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(
+            report.failures[0].file_name,
+            format!("b{}broken.ts", std::path::MAIN_SEPARATOR)
+        );
+
+        assert!(crate::analyze_files(&repo_path, &config, &HashSet::new())
+            .unwrap()
+            .files
+            .is_empty());
+    }
+
+    #[test]
+    fn test_analyze_paths() {
+        let dir = create_project();
+        fs::write(dir.path().join("a").join(".ignore"), "ignored.ts\n").unwrap();
+        fs::write(dir.path().join("a").join("ignored.ts"), "const a = 1;\n").unwrap();
+        let repo_path = dir.path().to_str().unwrap().to_string();
+        let mut config = get_default_config();
+        config.exclude_under = 0;
+
+        let paths: HashSet<PathBuf> = [
+            PathBuf::from("a").join("01.ts"),
+            PathBuf::from("a").join("ignored.ts"),
+            PathBuf::from("c").join("missing.ts"),
+        ]
+        .into_iter()
+        .collect();
+        let report = crate::analyze_paths(&repo_path, &config, &paths).unwrap();
+
+        // Only the given files are analyzed, and ignore files still apply to them
+        assert_eq!(
+            report
+                .files
+                .iter()
+                .map(|file_data| file_data.file_name.as_str())
+                .collect::<Vec<_>>(),
+            vec![format!("a{}01.ts", std::path::MAIN_SEPARATOR)]
+        );
+    }
+
+    #[test]
+    fn test_analyze_buffer() {
+        let mut config = get_default_config();
+        config.score_cap = 1;
+
+        let report = match crate::analyze_buffer("src/a.tsx", "const a = <div>{1}</div>;", &config)
+        {
+            Err(FtaError::ScoreCapBreached {
+                breaches, report, ..
+            }) => {
+                assert_eq!(breaches[0].file_name, "src/a.tsx");
+                report
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        };
+        assert_eq!(report.files.len(), 1);
+
+        let report = crate::analyze_buffer("src/a.ts", "function (", &config).unwrap();
+        assert!(report.files.is_empty());
+        assert_eq!(report.failures[0].file_name, "src/a.ts");
+    }
+}
@@ -0,0 +1,220 @@
+use crate::error::FtaError;
+use crate::structs::{AnalysisReport, FileData, FtaConfigResolved};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+mod tests;
+
+/// Editors tend to write a file in several steps, so changes are collected until the project has
+/// been quiet for this long.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone)]
+pub struct WatchError {
+    message: String,
+}
+
+impl fmt::Display for WatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WatchError! {}", self.message)
+    }
+}
+
+/// How the score of a file changed after it was saved.
+#[derive(Debug, PartialEq)]
+pub struct ScoreDelta {
+    pub file_name: String,
+    /// The previous score, or `None` for new files.
+    pub previous: Option<f64>,
+    /// The new score, or `None` if the file was removed or is no longer analyzed.
+    pub current: Option<f64>,
+    pub assessment: Option<String>,
+}
+
+/// The latest score of every analyzed file.
+#[derive(Debug, Default)]
+pub struct Scores {
+    scores: BTreeMap<String, f64>,
+}
+
+impl Scores {
+    pub fn new(files: &[FileData]) -> Scores {
+        Scores {
+            scores: files
+                .iter()
+                .map(|file_data| (file_data.file_name.clone(), file_data.fta_score))
+                .collect(),
+        }
+    }
+
+    /// Records the analysis of the `changed` files, returning how their scores changed. Files that
+    /// can't be parsed keep their previous score, as they are most likely being edited.
+    pub fn update(
+        &mut self,
+        changed: &HashSet<PathBuf>,
+        report: &AnalysisReport,
+    ) -> Vec<ScoreDelta> {
+        let mut changed: Vec<String> = changed
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        changed.sort();
+
+        let mut deltas = Vec::new();
+        for file_name in changed {
+            if report
+                .failures
+                .iter()
+                .any(|failure| failure.file_name == file_name)
+            {
+                continue;
+            }
+
+            let file_data = report
+                .files
+                .iter()
+                .find(|file_data| file_data.file_name == file_name);
+            let previous = match file_data {
+                Some(file_data) => self.scores.insert(file_name.clone(), file_data.fta_score),
+                None => self.scores.remove(&file_name),
+            };
+            if previous.is_none() && file_data.is_none() {
+                continue;
+            }
+
+            deltas.push(ScoreDelta {
+                file_name,
+                previous,
+                current: file_data.map(|file_data| file_data.fta_score),
+                assessment: file_data.map(|file_data| file_data.assessment.clone()),
+            });
+        }
+
+        deltas
+    }
+}
+
+pub fn format_score_deltas(deltas: &[ScoreDelta]) -> String {
+    deltas
+        .iter()
+        .map(|delta| match (delta.previous, delta.current) {
+            (Some(previous), Some(current)) if previous == current => {
+                format!("  {}: {:.2} (unchanged)", delta.file_name, current)
+            }
+            (Some(previous), Some(current)) => format!(
+                "  {}: {:.2} -> {:.2} ({:+.2}) {}",
+                delta.file_name,
+                previous,
+                current,
+                current - previous,
+                delta.assessment.as_deref().unwrap_or_default()
+            ),
+            (None, Some(current)) => format!(
+                "  {}: {:.2} (new) {}",
+                delta.file_name,
+                current,
+                delta.assessment.as_deref().unwrap_or_default()
+            ),
+            (Some(previous), None) => {
+                format!("  {}: removed (was {:.2})", delta.file_name, previous)
+            }
+            (None, None) => format!("  {}: removed", delta.file_name),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// The changed source files of an event, relative to `repo_path`. This leaves out the cache, which
+/// the analysis itself writes to, as it only holds JSON.
+fn changed_paths(
+    event: &notify::Event,
+    repo_path: &Path,
+    config: &FtaConfigResolved,
+) -> Vec<PathBuf> {
+    // Reading files during the analysis causes access events, which must not trigger another one
+    if !matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) {
+        return Vec::new();
+    }
+
+    event
+        .paths
+        .iter()
+        .filter(|path| {
+            let file_name = path.to_string_lossy();
+            config.extensions.iter().any(|ext| file_name.ends_with(ext))
+        })
+        .filter_map(|path| path.strip_prefix(repo_path).ok())
+        .map(Path::to_path_buf)
+        .collect()
+}
+
+/// Watches `repo_path` and re-analyzes files as they change, calling `on_change` with the changed
+/// scores. `files` is the analysis the changes are compared against. Files that can't be parsed
+/// are logged as usual. Only returns if watching fails.
+pub fn watch<F>(
+    repo_path: &String,
+    config: &FtaConfigResolved,
+    files: &[FileData],
+    mut on_change: F,
+) -> Result<(), WatchError>
+where
+    F: FnMut(&[ScoreDelta]),
+{
+    let unable_to_watch = |err: &dyn fmt::Display| WatchError {
+        message: format!("Unable to watch {}: {}", repo_path, err),
+    };
+
+    // Events may be reported with absolute paths, which are made relative to the project again
+    let repo_root = fs::canonicalize(repo_path).map_err(|err| unable_to_watch(&err))?;
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).map_err(|err| unable_to_watch(&err))?;
+    watcher
+        .watch(&repo_root, RecursiveMode::Recursive)
+        .map_err(|err| unable_to_watch(&err))?;
+
+    let mut scores = Scores::new(files);
+
+    while let Ok(event) = receiver.recv() {
+        let mut changed = HashSet::new();
+        let mut event = Some(event);
+        while let Some(result) = event {
+            match result {
+                Ok(event) => changed.extend(changed_paths(&event, &repo_root, config)),
+                Err(err) => log::warn!("Error while watching {}: {}", repo_path, err),
+            }
+            event = receiver.recv_timeout(DEBOUNCE).ok();
+        }
+        if changed.is_empty() {
+            continue;
+        }
+
+        // Only the changed files are analyzed, with the usual gitignore and exclusion rules, and
+        // merged into the previous scores
+        let report = match crate::analyze_paths(repo_path, config, &changed) {
+            Ok(report) => report,
+            Err(FtaError::ScoreCapBreached { report, .. }) => *report,
+            Err(err) => {
+                return Err(WatchError {
+                    message: err.to_string(),
+                })
+            }
+        };
+
+        let deltas = scores.update(&changed, &report);
+        if !deltas.is_empty() {
+            on_change(&deltas);
+        }
+    }
+
+    Err(WatchError {
+        message: format!("Stopped watching {}", repo_path),
+    })
+}
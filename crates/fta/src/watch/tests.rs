@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod tests {
+    use crate::config::get_default_config;
+    use crate::error::ParseFailure;
+    use crate::structs::{test_file_data, AnalysisReport};
+    use crate::watch::{changed_paths, format_score_deltas, ScoreDelta, Scores};
+    use notify::event::{AccessKind, CreateKind, ModifyKind};
+    use notify::{Event, EventKind};
+    use std::collections::HashSet;
+    use std::path::{Path, PathBuf};
+
+    fn paths(file_names: &[&str]) -> HashSet<PathBuf> {
+        file_names.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn test_score_deltas() {
+        let mut scores = Scores::new(&[
+            test_file_data("changed.ts", 40.0),
+            test_file_data("same.ts", 20.0),
+            test_file_data("removed.ts", 30.0),
+            test_file_data("broken.ts", 10.0),
+        ]);
+        let report = AnalysisReport {
+            files: vec![
+                test_file_data("changed.ts", 45.5),
+                test_file_data("same.ts", 20.0),
+                test_file_data("new.ts", 12.0),
+            ],
+            failures: vec![ParseFailure {
+                file_name: "broken.ts".to_string(),
+                message: "Expression expected".to_string(),
+                line: 1,
+                column: 1,
+            }],
+        };
+
+        let deltas = scores.update(
+            &paths(&[
+                "changed.ts",
+                "same.ts",
+                "new.ts",
+                "removed.ts",
+                "broken.ts",
+                "ignored.ts",
+            ]),
+            &report,
+        );
+
+        assert_eq!(
+            deltas
+                .iter()
+                .map(|delta| (delta.file_name.as_str(), delta.previous, delta.current))
+                .collect::<Vec<_>>(),
+            vec![
+                ("changed.ts", Some(40.0), Some(45.5)),
+                ("new.ts", None, Some(12.0)),
+                ("removed.ts", Some(30.0), None),
+                ("same.ts", Some(20.0), Some(20.0)),
+            ]
+        );
+
+        // The new scores are what the next change is compared against
+        let report = AnalysisReport {
+            files: vec![
+                test_file_data("changed.ts", 41.0),
+                test_file_data("broken.ts", 11.0),
+            ],
+            failures: vec![],
+        };
+        let deltas = scores.update(&paths(&["changed.ts", "broken.ts"]), &report);
+        assert_eq!(
+            deltas
+                .iter()
+                .map(|delta| (delta.file_name.as_str(), delta.previous, delta.current))
+                .collect::<Vec<_>>(),
+            vec![
+                ("broken.ts", Some(10.0), Some(11.0)),
+                ("changed.ts", Some(45.5), Some(41.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_score_deltas() {
+        let delta = |file_name: &str, previous: Option<f64>, current: Option<f64>| ScoreDelta {
+            file_name: file_name.to_string(),
+            previous,
+            current,
+            assessment: current.map(|_| "OK".to_string()),
+        };
+
+        assert_eq!(
+            format_score_deltas(&[
+                delta("a.ts", Some(40.0), Some(45.5)),
+                delta("b.ts", Some(40.0), Some(32.25)),
+                delta("c.ts", Some(20.0), Some(20.0)),
+                delta("d.ts", None, Some(12.0)),
+                delta("e.ts", Some(30.0), None),
+            ]),
+            "  a.ts: 40.00 -> 45.50 (+5.50) OK\n  b.ts: 40.00 -> 32.25 (-7.75) OK\n  c.ts: 20.00 (unchanged)\n  d.ts: 12.00 (new) OK\n  e.ts: removed (was 30.00)"
+        );
+    }
+
+    #[test]
+    fn test_changed_paths() {
+        let config = get_default_config();
+        let event = |kind: EventKind, path: &str| Event::new(kind).add_path(PathBuf::from(path));
+        let project = Path::new("/project");
+
+        assert_eq!(
+            changed_paths(
+                &event(EventKind::Modify(ModifyKind::Any), "/project/src/a.ts"),
+                project,
+                &config
+            ),
+            vec![PathBuf::from("src/a.ts")]
+        );
+        assert_eq!(
+            changed_paths(
+                &event(EventKind::Create(CreateKind::File), "/project/b.tsx"),
+                project,
+                &config
+            ),
+            vec![PathBuf::from("b.tsx")]
+        );
+        // Reading a file doesn't change it
+        assert!(changed_paths(
+            &event(EventKind::Access(AccessKind::Any), "/project/src/a.ts"),
+            project,
+            &config
+        )
+        .is_empty());
+        // Only source files are re-analyzed, which leaves out the cache and editor swap files
+        assert!(changed_paths(
+            &event(
+                EventKind::Modify(ModifyKind::Any),
+                "/project/.fta-cache/cache.json"
+            ),
+            project,
+            &config
+        )
+        .is_empty());
+        assert!(changed_paths(
+            &event(EventKind::Modify(ModifyKind::Any), "/project/src/.a.ts.swp"),
+            project,
+            &config
+        )
+        .is_empty());
+    }
+}
@@ -4,6 +4,7 @@ declare module "fta-cli" {
    *
    * @property {string} file_name - The name of the file.
    * @property {number} cyclo - The cyclomatic complexity of the file.
+   * @property {number} cognitive - The cognitive complexity of the file.
    * @property {Object} halstead - The Halstead metrics of the file, a complexity measure.
    * @property {number} halstead.uniq_operators - The number of unique operators.
    * @property {number} halstead.uniq_operands - The number of unique operands.
@@ -19,6 +20,9 @@ declare module "fta-cli" {
    * @property {number} line_count - The number of lines in the file.
    * @property {number} fta_score - The FTA score of the file.
    * @property {string} assessment - The assessment of the file.
+   * @property {Severity} severity - The severity of the file's assessment band.
+   * @property {AnalyzedFunction[]} functions - The metrics of each function in the file.
+   * @property {Suppression} [suppression] - The suppression comment of the file, if any.
    */
   export type AnalyzedFile = {
     /**
@@ -33,6 +37,12 @@ declare module "fta-cli" {
      * @type {number}
      */
     cyclo: number;
+    /**
+     * The cognitive complexity of the file. Unlike `cyclo`, deeply nested structures are penalised more than flat ones.
+     *
+     * @type {number}
+     */
+    cognitive: number;
     /**
      * The Halstead metrics of the file, a complexity measure.
      * For further information see the [docs](https://ftaproject.dev/docs/scoring)
@@ -125,6 +135,149 @@ declare module "fta-cli" {
      * @type {string}
      */
     assessment: string;
+    /**
+     * The severity of the file's assessment band, as configured with `assessment_bands`.
+     *
+     * @type {Severity}
+     */
+    severity: Severity;
+    /**
+     * The metrics of each function, method, getter/setter and arrow function in the file, in source order.
+     *
+     * @type {AnalyzedFunction[]}
+     */
+    functions: AnalyzedFunction[];
+    /**
+     * The `fta-ignore-file` or `fta-score-cap` comment of the file, if it has one.
+     *
+     * @type {Suppression}
+     */
+    suppression?: Suppression;
+  };
+
+  /**
+   * An inline comment that opts a file or function out of checks.
+   *
+   * @property {string} kind - `ignore-file`, `ignore-function` (for `fta-ignore-next-function`) or `score-cap`.
+   * @property {number} line - The line of the comment.
+   * @property {string | null} reason - The reason given after `--` in the comment.
+   * @property {number} [score_cap] - The score cap of the file, for `score-cap` suppressions.
+   */
+  export type Suppression =
+    | { kind: "ignore-file" | "ignore-function"; line: number; reason: string | null }
+    | { kind: "score-cap"; line: number; score_cap: number; reason: string | null };
+
+  /**
+   * The severity of an assessment band.
+   */
+  export type Severity = "none" | "note" | "warning" | "error";
+
+  /**
+   * Represents a single function within an analyzed file.
+   *
+   * @property {string} name - The name of the function, e.g. `handleClick` or `Counter.increment`.
+   * @property {number} start_line - The line the function starts on.
+   * @property {number} end_line - The line the function ends on.
+   * @property {number} cyclo - The cyclomatic complexity of the function.
+   * @property {number} cognitive - The cognitive complexity of the function.
+   * @property {Object} halstead - The Halstead metrics of the function.
+   * @property {number} line_count - The number of lines in the function.
+   * @property {number} fta_score - The FTA score of the function.
+   * @property {Suppression} [suppression] - The `fta-ignore-next-function` comment of the function, if any.
+   */
+  export type AnalyzedFunction = Pick<AnalyzedFile, "cyclo" | "cognitive" | "halstead" | "line_count" | "fta_score"> & {
+    /**
+     * The name of the function, e.g. `handleClick` or `Counter.increment`.
+     *
+     * @type {string}
+     */
+    name: string;
+    /**
+     * The line the function starts on.
+     *
+     * @type {number}
+     */
+    start_line: number;
+    /**
+     * The line the function ends on.
+     *
+     * @type {number}
+     */
+    end_line: number;
+    /**
+     * The `fta-ignore-next-function` comment of the function, if it has one.
+     *
+     * @type {Suppression}
+     */
+    suppression?: Suppression;
+  };
+
+  /**
+   * Summary statistics of an analysis, included in the JSON output with `--summary`.
+   *
+   * @property {number} file_count - The number of analyzed files.
+   * @property {number} line_count - The total number of lines of the analyzed files.
+   * @property {ParseFailure[]} failures - The files that couldn't be parsed.
+   * @property {number} elapsed - The time the analysis took, in seconds.
+   * @property {Object | null} scores - Statistics of the FTA scores, or `null` if no files were analyzed.
+   * @property {Object[]} histogram - The number of files per range of FTA scores.
+   * @property {Object[]} assessments - The number of files in each assessment band.
+   * @property {Object[]} suppressed - The files and functions with suppression comments.
+   */
+  export type FtaSummary = {
+    file_count: number;
+    line_count: number;
+    failures: ParseFailure[];
+    elapsed: number;
+    scores: {
+      mean: number;
+      median: number;
+      p75: number;
+      p90: number;
+      p95: number;
+      max: number;
+    } | null;
+    /**
+     * Files with a score of at least `min` and below `max`. The last bucket has no `max`.
+     *
+     * @type {Object[]}
+     */
+    histogram: { min: number; max: number | null; count: number }[];
+    assessments: { assessment: string; severity: Severity; count: number }[];
+    /**
+     * The files and functions with suppression comments. `function` is `null` for file suppressions.
+     *
+     * @type {Object[]}
+     */
+    suppressed: ({ file_name: string; function: string | null } & Suppression)[];
+  };
+
+  /**
+   * A file that couldn't be parsed, and is missing from the report.
+   *
+   * @property {string} file_name - The name of the file.
+   * @property {string} message - The message of the syntax error.
+   * @property {number} line - The line of the syntax error.
+   * @property {number} column - The column of the syntax error.
+   */
+  export type ParseFailure = {
+    file_name: string;
+    message: string;
+    line: number;
+    column: number;
+  };
+
+  /**
+   * The JSON output with `--summary`.
+   *
+   * @property {string} version - The version of FTA.
+   * @property {FtaSummary} summary - Summary statistics of the analysis.
+   * @property {AnalyzedFile[]} files - The analyzed files.
+   */
+  export type FtaReport = {
+    version: string;
+    summary: FtaSummary;
+    files: AnalyzedFile[];
   };
 
   /**